@@ -0,0 +1,341 @@
+use automaton::render::{Draw, Frame, Renderer};
+use common::answers::{self, Answers, Verdict};
+use common::bench;
+use common::cli::{Options, Part, OPTIONS_HELP};
+use common::error::Diagnostic;
+use common::input::{self, Source};
+use common::solution::{self, print_answers, Answer, Solution, Solver};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+
+const YEARS: [u16; 2] = [2020, 2021];
+
+fn solver_2020(day: u8) -> Option<Solver> {
+    Some(match day {
+        1 => Solver::of::<puzzle1_1::Puzzle>(),
+        2 => Solver::of::<puzzle2::Puzzle>(),
+        3 => Solver::of::<puzzle3::Puzzle>(),
+        4 => Solver::of::<puzzle4::Puzzle>(),
+        5 => Solver::of::<puzzle5::Puzzle>(),
+        6 => Solver::of::<puzzle6::Puzzle>(),
+        7 => Solver::of::<puzzle7::Puzzle>(),
+        8 => Solver::of::<puzzle8::Puzzle>(),
+        9 => Solver::of::<puzzle9::Puzzle>(),
+        10 => Solver::of::<puzzle10::Puzzle>(),
+        11 => Solver::of::<puzzle11::Puzzle>(),
+        12 => Solver::of::<puzzle12::Puzzle>(),
+        13 => Solver::of::<puzzle13::Puzzle>(),
+        14 => Solver::of::<puzzle14::Puzzle>(),
+        15 => Solver::of::<puzzle15::Puzzle>(),
+        16 => Solver::of::<puzzle16::Puzzle>(),
+        17 => Solver::of::<puzzle17::Puzzle>(),
+        18 => Solver::of::<puzzle18::Puzzle>(),
+        19 => Solver::of::<puzzle19::Puzzle>(),
+        20 => Solver::of::<puzzle20::Puzzle>(),
+        21 => Solver::of::<puzzle21::Puzzle>(),
+        22 => Solver::of::<puzzle22::Puzzle>(),
+        23 => Solver::of::<puzzle23::Puzzle>(),
+        24 => Solver::of::<puzzle24::Puzzle>(),
+        25 => Solver::of::<puzzle25::Puzzle>(),
+        _ => return None,
+    })
+}
+
+fn solver(year: u16, day: u8) -> Option<Solver> {
+    match year {
+        2020 => solver_2020(day),
+        2021 => advent2021::solver(day),
+        _ => None,
+    }
+}
+
+fn usage() -> String {
+    let days_by_year: Vec<_> = YEARS
+        .iter()
+        .map(|&year| {
+            let days: Vec<_> = (1..=25)
+                .filter(|&day| solver(year, day).is_some())
+                .map(|day| day.to_string())
+                .collect();
+            format!("  {}: {}", year, days.join(" "))
+        })
+        .collect();
+    format!(
+        "Usage: advent [OPTIONS] <year> <day>
+       advent verify [<year> [<day>]]
+       advent bench [<year> [<day>]]
+       advent render <year> <day> <output>\n\n{}\n\nAvailable days:\n{}",
+        OPTIONS_HELP,
+        days_by_year.join("\n")
+    )
+}
+
+fn parse_year(year: &str) -> Result<u16, String> {
+    year.parse()
+        .ok()
+        .filter(|year| YEARS.contains(year))
+        .ok_or_else(|| format!("Unknown year {}", year))
+}
+
+fn parse_day(year: u16, puzzle: &str) -> Result<(u8, Solver), String> {
+    puzzle
+        .parse()
+        .ok()
+        .and_then(|day| Some((day, solver(year, day)?)))
+        .ok_or_else(|| format!("Unknown puzzle {}", puzzle))
+}
+
+fn run(options: &Options, year: &str, puzzle: &str) -> Result<(), Box<dyn Error>> {
+    let year = parse_year(year)?;
+    let (day, solver) = parse_day(year, puzzle)?;
+    let input = input::read(&options.source(year, day))?;
+    let answers = (solver.solve)(&input, options.part)?;
+    print_answers(options.part, &answers);
+    Ok(())
+}
+
+// All days, all days in a year, or one day, for the commands that take an
+// optional year and day
+fn select_days(args: &[String]) -> Result<Vec<(u16, u8, Solver)>, String> {
+    Ok(match args {
+        [] => YEARS
+            .iter()
+            .flat_map(|&year| (1..=25).map(move |day| (year, day)))
+            .filter_map(|(year, day)| Some((year, day, solver(year, day)?)))
+            .collect(),
+        [year] => {
+            let year = parse_year(year)?;
+            (1..=25)
+                .filter_map(|day| Some((year, day, solver(year, day)?)))
+                .collect()
+        }
+        [year, puzzle] => {
+            let year = parse_year(year)?;
+            let (day, solver) = parse_day(year, puzzle)?;
+            vec![(year, day, solver)]
+        }
+        _ => return Err("Takes at most a year and a puzzle number".into()),
+    })
+}
+
+#[derive(Default)]
+struct Tally {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+}
+
+fn verify_day(
+    answers: &Answers,
+    year: u16,
+    day: u8,
+    solver: Solver,
+    part: Part,
+    tally: &mut Tally,
+) {
+    let results = input::read(&Source::Puzzle { year, day })
+        .map_err(Box::<dyn Error>::from)
+        .and_then(|input| (solver.solve)(&input, part));
+    let results = match results {
+        Ok(results) => results,
+        Err(err) => {
+            println!("{} day {}: FAIL\n{}", year, day, err);
+            tally.failed += 1;
+            return;
+        }
+    };
+    for (is_part2, answer) in part.part2_flags().zip(&results) {
+        let label = format!("{} day {} part {}", year, day, is_part2 as u8 + 1);
+        match answers.check(year, day, is_part2, answer) {
+            Verdict::Pass => {
+                println!("{}: pass", label);
+                tally.passed += 1;
+            }
+            Verdict::Fail { expected } => {
+                println!("{}: FAIL (expected {}, got {})", label, expected, answer);
+                tally.failed += 1;
+            }
+            // Parts without an answer, like 2020 day 25 part 2, aren't recorded
+            Verdict::Missing if *answer == Answer::None => {}
+            Verdict::Missing => {
+                println!("{}: missing (got {})", label, answer);
+                tally.missing += 1;
+            }
+        }
+    }
+}
+
+// Solves every selected day against its real input and compares with the
+// answers recorded in answers.toml. Returns whether nothing failed.
+fn verify(options: &Options, args: &[String]) -> Result<bool, Box<dyn Error>> {
+    if options.input.is_some() || options.example {
+        return Err("verify always uses the real puzzle input".into());
+    }
+    let answers = Answers::load(&answers::answers_path())?;
+    let days = select_days(args)?;
+
+    let mut tally = Tally::default();
+    for (year, day, solver) in days {
+        verify_day(&answers, year, day, solver, options.part, &mut tally);
+    }
+    println!(
+        "{} passed, {} failed, {} missing",
+        tally.passed, tally.failed, tally.missing
+    );
+    Ok(tally.failed == 0)
+}
+
+fn bench(options: &Options, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut rows = vec![];
+    for (year, day, solver) in select_days(args)? {
+        let input = input::read(&options.source(year, day))?;
+        let results = (solver.bench)(&input, options.part, options.iterations)
+            .inspect_err(|_| eprintln!("{} day {}:", year, day))?;
+        rows.extend(results.into_iter().map(|(stage, stats)| bench::Row {
+            year,
+            day,
+            stage,
+            stats,
+        }));
+    }
+    bench::sort_rows(&mut rows);
+    if options.json {
+        println!("{}", bench::format_json(&rows, options.iterations));
+    } else {
+        print!("{}", bench::format_table(&rows));
+    }
+    Ok(())
+}
+
+type DrawResult = Result<(Renderer, Vec<Frame>, Option<String>), Box<dyn Error>>;
+
+fn draw<S: Solution + Draw>(input: &str, is_part2: bool) -> DrawResult {
+    let puzzle = solution::parse::<S>(input)?;
+    Ok((
+        puzzle.renderer(),
+        puzzle.frames(is_part2),
+        puzzle.legend(is_part2),
+    ))
+}
+
+// The days that can be drawn
+fn drawing(year: u16, day: u8, input: &str, is_part2: bool) -> Option<DrawResult> {
+    Some(match (year, day) {
+        (2020, 11) => draw::<puzzle11::Puzzle>(input, is_part2),
+        (2020, 17) => draw::<puzzle17::Puzzle>(input, is_part2),
+        (2020, 20) => draw::<puzzle20::Puzzle>(input, is_part2),
+        (2020, 24) => draw::<puzzle24::Puzzle>(input, is_part2),
+        (2021, 9) => draw::<advent2021::puzzle9::Puzzle>(input, is_part2),
+        _ => return None,
+    })
+}
+
+// Draws each generation of part 1, or of part 2 with --part 2. The output's
+// extension picks the format: .gif for an animation, .png for one numbered
+// image per generation, and anything else, or - for stdout, for ASCII art. A
+// legend goes before the ASCII art, or to stdout alongside images.
+fn render(options: &Options, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (year, puzzle, output) = match args {
+        [year, puzzle, output] => (parse_year(year)?, puzzle, Path::new(output)),
+        _ => return Err("Requires a year, a puzzle number, and an output file".into()),
+    };
+    let (day, _) = parse_day(year, puzzle)?;
+    let input = input::read(&options.source(year, day))?;
+    let is_part2 = options.part == Part::Two;
+    let (renderer, frames, legend) = drawing(year, day, &input, is_part2)
+        .ok_or_else(|| format!("{} day {} can't be drawn", year, day))??;
+
+    const DELAY: u16 = 10;
+    let extension = output.extension().and_then(|ext| ext.to_str());
+    if let (Some(legend), Some("gif" | "png")) = (&legend, extension) {
+        print!("{}", legend);
+    }
+    match extension {
+        Some("gif") => renderer.gif(&frames, DELAY, BufWriter::new(File::create(output)?))?,
+        Some("png") => {
+            let stem = output.with_extension("");
+            for (generation, frame) in frames.iter().enumerate() {
+                let path = format!("{}-{:03}.png", stem.display(), generation);
+                renderer.png(frame, BufWriter::new(File::create(path)?))?;
+            }
+        }
+        _ => {
+            let mut writer: Box<dyn Write> = if output.as_os_str() == "-" {
+                Box::new(io::stdout().lock())
+            } else {
+                Box::new(BufWriter::new(File::create(output)?))
+            };
+            if let Some(legend) = legend {
+                writeln!(writer, "{}", legend)?;
+            }
+            for (generation, frame) in frames.iter().enumerate() {
+                writeln!(
+                    writer,
+                    "Generation {}:\n{}",
+                    generation,
+                    renderer.ascii(frame)
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn fail(err: impl fmt::Display) -> ! {
+    eprintln!("{}\nRun with --help for usage.", err);
+    process::exit(1);
+}
+
+// Problems with the input aren't usage errors, so they don't get the hint.
+// Output cut short by whatever reads it, as with `advent render ... - | head`,
+// isn't an error at all.
+fn report(err: Box<dyn Error>) -> ! {
+    if err
+        .downcast_ref::<io::Error>()
+        .is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe)
+    {
+        process::exit(0);
+    }
+    if err.is::<Diagnostic>() {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+    fail(err);
+}
+
+fn main() {
+    let options = Options::parse(env::args().skip(1)).unwrap_or_else(|err| fail(err));
+    if options.help {
+        println!("{}", usage());
+        return;
+    }
+    let (year, puzzle) = match options.args.as_slice() {
+        [command, args @ ..] if command == "verify" => match verify(&options, args) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(err) => fail(err),
+        },
+        [command, args @ ..] if command == "bench" => {
+            if let Err(err) = bench(&options, args) {
+                report(err);
+            }
+            return;
+        }
+        [command, args @ ..] if command == "render" => {
+            if let Err(err) = render(&options, args) {
+                report(err);
+            }
+            return;
+        }
+        [year, puzzle] => (year, puzzle),
+        _ => fail("Requires a year and a puzzle number"),
+    };
+    if let Err(err) = run(&options, year, puzzle) {
+        report(err);
+    }
+}
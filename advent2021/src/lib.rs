@@ -0,0 +1,31 @@
+use common::solution::Solver;
+
+#[macro_use]
+extern crate scan_fmt;
+
+pub mod puzzle1;
+pub mod puzzle10;
+pub mod puzzle2;
+pub mod puzzle3;
+pub mod puzzle4;
+pub mod puzzle5;
+pub mod puzzle6;
+pub mod puzzle7;
+pub mod puzzle8;
+pub mod puzzle9;
+
+pub fn solver(day: u8) -> Option<Solver> {
+    Some(match day {
+        1 => Solver::of::<puzzle1::Puzzle>(),
+        2 => Solver::of::<puzzle2::Puzzle>(),
+        3 => Solver::of::<puzzle3::Puzzle>(),
+        4 => Solver::of::<puzzle4::Puzzle>(),
+        5 => Solver::of::<puzzle5::Puzzle>(),
+        6 => Solver::of::<puzzle6::Puzzle>(),
+        7 => Solver::of::<puzzle7::Puzzle>(),
+        8 => Solver::of::<puzzle8::Puzzle>(),
+        9 => Solver::of::<puzzle9::Puzzle>(),
+        10 => Solver::of::<puzzle10::Puzzle>(),
+        _ => return None,
+    })
+}
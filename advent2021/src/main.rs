@@ -1,40 +1,61 @@
+use common::cli::{Options, OPTIONS_HELP};
+use common::error::Diagnostic;
+use common::input;
+use common::solution::print_answers;
 use std::env;
+use std::error::Error;
+use std::fmt;
 use std::process;
 
-#[macro_use]
-extern crate scan_fmt;
+fn usage() -> String {
+    let days: Vec<_> = (1..=25)
+        .filter(|&day| advent2021::solver(day).is_some())
+        .map(|day| day.to_string())
+        .collect();
+    format!(
+        "Usage: advent2021 [OPTIONS] <day>\n\n{}\n\nAvailable days: {}",
+        OPTIONS_HELP,
+        days.join(" ")
+    )
+}
 
-mod puzzle1;
-mod puzzle10;
-mod puzzle2;
-mod puzzle3;
-mod puzzle4;
-mod puzzle5;
-mod puzzle6;
-mod puzzle7;
-mod puzzle8;
-mod puzzle9;
+fn run(options: &Options, puzzle: &str) -> Result<(), Box<dyn Error>> {
+    let (day, solver) = puzzle
+        .parse()
+        .ok()
+        .and_then(|day| Some((day, advent2021::solver(day)?)))
+        .ok_or_else(|| format!("Unknown puzzle {}", puzzle))?;
+    let input = input::read(&options.source(2021, day))?;
+    let answers = (solver.solve)(&input, options.part)?;
+    print_answers(options.part, &answers);
+    Ok(())
+}
 
-fn is_part2() -> bool {
-    env::args().nth(2).map(|val| val == "2").unwrap_or(false)
+fn fail(err: impl fmt::Display) -> ! {
+    eprintln!("{}\nRun with --help for usage.", err);
+    process::exit(1);
 }
 
-fn main() {
-    let puzzle = env::args().nth(1).unwrap_or_else(|| {
-        println!("Requires an argument");
+// Problems with the input aren't usage errors, so they don't get the hint
+fn report(err: Box<dyn Error>) -> ! {
+    if err.is::<Diagnostic>() {
+        eprintln!("{}", err);
         process::exit(1);
-    });
-    match puzzle.as_str() {
-        "1" => puzzle1::main(is_part2()),
-        "2" => puzzle2::main(is_part2()),
-        "3" => puzzle3::main(is_part2()),
-        "4" => puzzle4::main(is_part2()),
-        "5" => puzzle5::main(is_part2()),
-        "6" => puzzle6::main(is_part2()),
-        "7" => puzzle7::main(is_part2()),
-        "8" => puzzle8::main(is_part2()),
-        "9" => puzzle9::main(),
-        "10" => puzzle10::main(),
-        _ => println!("Unknown puzzle {}", puzzle),
+    }
+    fail(err);
+}
+
+fn main() {
+    let options = Options::parse(env::args().skip(1)).unwrap_or_else(|err| fail(err));
+    if options.help {
+        println!("{}", usage());
+        return;
+    }
+    let puzzle = match options.args.as_slice() {
+        [puzzle] => puzzle,
+        _ => fail("Requires a puzzle number"),
+    };
+    if let Err(err) = run(&options, puzzle) {
+        report(err);
     }
 }
@@ -1,4 +1,24 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use itertools::Itertools;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum SonarError {
+    BadDepth(ParseIntError),
+}
+
+impl fmt::Display for SonarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SonarError::BadDepth(err) => write!(f, "Bad depth: {}", err),
+        }
+    }
+}
+
+impl Error for SonarError {}
 
 fn count_increases(measurements: impl Iterator<Item = i32>) -> usize {
     measurements
@@ -14,15 +34,28 @@ fn count_triplet_increases(measurements: impl Iterator<Item = i32>) -> usize {
         .count()
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle1");
-    let measurements = input.lines().map(|s| s.parse().unwrap());
-    let answer = if is_part2 {
-        count_triplet_increases(measurements)
-    } else {
-        count_increases(measurements)
-    };
-    println!("{}", answer);
+pub struct Puzzle {
+    measurements: Vec<i32>,
+}
+
+impl Solution for Puzzle {
+    type Error = SonarError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<SonarError>> {
+        let measurements = parse_lines(input, |line| {
+            line.parse()
+                .map_err(|err| ParseError::at_column(1, SonarError::BadDepth(err)))
+        })?;
+        Ok(Puzzle { measurements })
+    }
+
+    fn part1(&self) -> Answer {
+        count_increases(self.measurements.iter().copied()).into()
+    }
+
+    fn part2(&self) -> Answer {
+        count_triplet_increases(self.measurements.iter().copied()).into()
+    }
 }
 
 #[cfg(test)]
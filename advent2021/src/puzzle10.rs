@@ -1,3 +1,23 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum ChunkError {
+    BadCharacter(char),
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkError::BadCharacter(c) => write!(f, "Bad character {:?}, expected a bracket", c),
+        }
+    }
+}
+
+impl Error for ChunkError {}
+
 #[derive(Debug, PartialEq)]
 enum Bracket {
     Round,
@@ -6,8 +26,9 @@ enum Bracket {
     Angle,
 }
 
+// A closing bracket with nothing open counts as corrupted too
 fn expect(stack: &mut Vec<Bracket>, expected: Bracket) -> Result<(), Bracket> {
-    if stack.pop().unwrap() == expected {
+    if stack.pop().as_ref() == Some(&expected) {
         Ok(())
     } else {
         Err(expected)
@@ -26,7 +47,7 @@ fn check(line: &str) -> Result<Vec<Bracket>, Bracket> {
             b']' => expect(&mut stack, Bracket::Square)?,
             b'}' => expect(&mut stack, Bracket::Curly)?,
             b'>' => expect(&mut stack, Bracket::Angle)?,
-            _ => panic!("Unexpected character {}", b),
+            _ => unreachable!("Unexpected character {}", b), // checked in parse
         }
     }
     Ok(stack)
@@ -57,23 +78,42 @@ fn score_autocomplete(stack: Vec<Bracket>) -> usize {
         .sum::<usize>()
 }
 
-pub fn main() {
-    let input = include_str!("input/puzzle10");
-    println!(
-        "{}",
-        input
-            .lines()
+pub struct Puzzle {
+    lines: Vec<String>,
+}
+
+impl Solution for Puzzle {
+    type Error = ChunkError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<ChunkError>> {
+        let lines = parse_lines(input, |line| {
+            match line.char_indices().find(|(_, c)| !"()[]{}<>".contains(*c)) {
+                Some((ix, c)) => Err(ParseError::at_column(ix + 1, ChunkError::BadCharacter(c))),
+                None => Ok(line.to_string()),
+            }
+        })?;
+        Ok(Puzzle { lines })
+    }
+
+    fn part1(&self) -> Answer {
+        self.lines
+            .iter()
             .filter_map(|l| check(l).err())
             .map(score)
             .sum::<usize>()
-    );
-    let mut scores: Vec<usize> = input
-        .lines()
-        .filter_map(|l| check(l).ok())
-        .map(score_autocomplete)
-        .collect();
-    scores.sort();
-    println!("{}", scores[scores.len() / 2]);
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut scores: Vec<usize> = self
+            .lines
+            .iter()
+            .filter_map(|l| check(l).ok())
+            .map(score_autocomplete)
+            .collect();
+        scores.sort();
+        scores.get(scores.len() / 2).copied().into()
+    }
 }
 
 #[test]
@@ -90,6 +130,7 @@ fn test_bracket_check() {
     assert_eq!(check("{()()()>"), Err(Bracket::Angle));
     assert_eq!(check("(((()))}"), Err(Bracket::Curly));
     assert_eq!(check("<([]){()}[{}])"), Err(Bracket::Round));
+    assert_eq!(check("())"), Err(Bracket::Round));
 
     use Bracket::*;
     assert_eq!(
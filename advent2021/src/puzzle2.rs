@@ -1,4 +1,29 @@
-#[derive(Debug)]
+use common::error::{column_of, parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum CommandError {
+    BadDirection(String),
+    BadDistance(ParseIntError),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::BadDirection(dir) => {
+                write!(f, "Bad direction {:?}, expected forward, up, or down", dir)
+            }
+            CommandError::BadDistance(err) => write!(f, "Bad distance: {}", err),
+        }
+    }
+}
+
+impl Error for CommandError {}
+
+#[derive(Clone, Copy, Debug)]
 enum Direction {
     Forward(i32),
     Up(i32),
@@ -16,13 +41,20 @@ impl Direction {
         Self::Forward(2),
     ];
 
-    fn from_string(line: &str) -> Self {
-        let (keyword, num) = scan_fmt!(line, "{} {d}", String, i32).unwrap();
-        match keyword.as_str() {
-            "forward" => Self::Forward(num),
-            "up" => Self::Up(num),
-            "down" => Self::Down(num),
-            _ => panic!("Bad direction {}", line),
+    fn from_string(line: &str) -> Result<Self, ParseError<CommandError>> {
+        // Without a distance, point just past the end of the line
+        let (keyword, num) = line.split_once(' ').unwrap_or((line, &line[line.len()..]));
+        let dist = num.parse().map_err(|err| {
+            ParseError::at_column(column_of(line, num), CommandError::BadDistance(err))
+        })?;
+        match keyword {
+            "forward" => Ok(Self::Forward(dist)),
+            "up" => Ok(Self::Up(dist)),
+            "down" => Ok(Self::Down(dist)),
+            _ => Err(ParseError::at_column(
+                1,
+                CommandError::BadDirection(keyword.into()),
+            )),
         }
     }
 }
@@ -64,17 +96,39 @@ impl Sub {
     }
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle2");
-    let mut sub = Sub::new();
-    input.lines().map(Direction::from_string).for_each(|dir| {
-        if is_part2 {
-            sub.move_aim(dir);
-        } else {
-            sub.go(dir);
-        }
-    });
-    println!("{}", sub.position * sub.depth);
+pub struct Puzzle {
+    directions: Vec<Direction>,
+}
+
+impl Puzzle {
+    fn navigate(&self, is_part2: bool) -> i32 {
+        let mut sub = Sub::new();
+        self.directions.iter().for_each(|&dir| {
+            if is_part2 {
+                sub.move_aim(dir);
+            } else {
+                sub.go(dir);
+            }
+        });
+        sub.position * sub.depth
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = CommandError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<CommandError>> {
+        let directions = parse_lines(input, Direction::from_string)?;
+        Ok(Puzzle { directions })
+    }
+
+    fn part1(&self) -> Answer {
+        self.navigate(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.navigate(true).into()
+    }
 }
 
 #[test]
@@ -97,3 +151,24 @@ fn part2_example() {
     assert_eq!(sub.depth, 60);
     assert_eq!(sub.aim, 10);
 }
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Puzzle::parse("forward 5\nsideways 3").err(),
+        Some(ParseError::new(
+            2,
+            1,
+            CommandError::BadDirection("sideways".into())
+        ))
+    );
+    let err = Puzzle::parse("up x").err().unwrap();
+    assert_eq!((err.line, err.column), (1, 4));
+    assert!(matches!(err.kind, CommandError::BadDistance(_)));
+    // A line with no distance points just past its end
+    let input = "forward 5\ne\n";
+    let err = Puzzle::parse(input).err().unwrap();
+    assert_eq!((err.line, err.column), (2, 2));
+    assert!(matches!(err.kind, CommandError::BadDistance(_)));
+    assert!(err.diagnostic(input).ends_with("2 | e\n  |  ^"));
+}
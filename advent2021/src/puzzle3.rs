@@ -1,3 +1,33 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum ReportError {
+    NoNumbers,
+    NoBits,
+    BadBit(char),
+    WrongWidth { expected: usize, got: usize },
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::NoNumbers => write!(f, "Diagnostic report is empty"),
+            ReportError::NoBits => write!(f, "Number has 0 bits"),
+            ReportError::BadBit(c) => write!(f, "Bad bit {:?}, expected 0 or 1", c),
+            ReportError::WrongWidth { expected, got } => write!(
+                f,
+                "Number has {} bits, but the first one has {}",
+                got, expected
+            ),
+        }
+    }
+}
+
+impl Error for ReportError {}
+
 fn count_bits(input: &[&str]) -> Vec<usize> {
     let mut counts = vec![0; input[0].len()];
     for s in input.iter() {
@@ -49,22 +79,58 @@ fn calc_rating(lines: &[&str], bit_func: &impl Fn(usize, usize) -> bool) -> u64
     u64::from_str_radix(possibilities[0], 2).unwrap()
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle3");
-    let lines: Vec<&str> = input.lines().collect();
-    if is_part2 {
-        let oxygen = calc_rating(&lines, &oxygen_bit);
-        let co2 = calc_rating(&lines, &co2_bit);
-        println!("{}", oxygen * co2);
-    } else {
+pub struct Puzzle {
+    lines: Vec<String>,
+}
+
+impl Puzzle {
+    fn lines(&self) -> Vec<&str> {
+        self.lines.iter().map(String::as_str).collect()
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = ReportError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<ReportError>> {
+        let width = input.lines().next().map(str::len);
+        let expected = width.ok_or_else(|| ParseError::new(1, 1, ReportError::NoNumbers))?;
+        if expected == 0 {
+            return Err(ParseError::new(1, 1, ReportError::NoBits));
+        }
+        let lines = parse_lines(input, |line| {
+            if let Some((ix, c)) = line.char_indices().find(|&(_, c)| c != '0' && c != '1') {
+                return Err(ParseError::at_column(ix + 1, ReportError::BadBit(c)));
+            }
+            if line.len() != expected {
+                let got = line.len();
+                return Err(ParseError::at_column(
+                    1,
+                    ReportError::WrongWidth { expected, got },
+                ));
+            }
+            Ok(line.to_string())
+        })?;
+        Ok(Puzzle { lines })
+    }
+
+    fn part1(&self) -> Answer {
+        let lines = self.lines();
         let counts = count_bits(&lines);
         let (gamma, epsilon) = calc_power_consumption(&counts, lines.len());
-        println!("{}", gamma * epsilon);
+        (gamma * epsilon).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let lines = self.lines();
+        let oxygen = calc_rating(&lines, &oxygen_bit);
+        let co2 = calc_rating(&lines, &co2_bit);
+        (oxygen * co2).into()
     }
 }
 
 #[cfg(test)]
-static EXAMPLE_INPUT: [&'static str; 12] = [
+static EXAMPLE_INPUT: [&str; 12] = [
     "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000", "11001",
     "00010", "01010",
 ];
@@ -85,3 +151,34 @@ fn part2_example() {
     let co2 = calc_rating(&EXAMPLE_INPUT, &co2_bit);
     assert_eq!(co2, 10);
 }
+
+#[test]
+fn parse_errors() {
+    assert_eq!(
+        Puzzle::parse("00100\n11210\n").err(),
+        Some(ParseError::new(2, 3, ReportError::BadBit('2')))
+    );
+    assert_eq!(
+        Puzzle::parse("00100\n1111\n").err(),
+        Some(ParseError::new(
+            2,
+            1,
+            ReportError::WrongWidth {
+                expected: 5,
+                got: 4
+            }
+        ))
+    );
+    assert_eq!(
+        Puzzle::parse("").err(),
+        Some(ParseError::new(1, 1, ReportError::NoNumbers))
+    );
+    assert_eq!(
+        Puzzle::parse("\n").err(),
+        Some(ParseError::new(1, 1, ReportError::NoBits))
+    );
+    assert_eq!(
+        Puzzle::parse("\n00100\n").err(),
+        Some(ParseError::new(1, 1, ReportError::NoBits))
+    );
+}
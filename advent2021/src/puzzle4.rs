@@ -1,4 +1,33 @@
+use common::error::{column_of, lines_before, ParseError};
+use common::solution::{Answer, Solution};
 use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum BingoError {
+    MissingDraws,
+    BadNumber(ParseIntError),
+    BadBoardSize,
+}
+
+impl fmt::Display for BingoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BingoError::MissingDraws => write!(f, "Missing the line of numbers drawn"),
+            BingoError::BadNumber(err) => write!(f, "Bad number: {}", err),
+            BingoError::BadBoardSize => write!(f, "Boards must be 5 rows of 5 numbers"),
+        }
+    }
+}
+
+impl Error for BingoError {}
+
+fn parse_number(line: &str, s: &str) -> Result<u8, ParseError<BingoError>> {
+    s.parse()
+        .map_err(|err| ParseError::at_column(column_of(line, s), BingoError::BadNumber(err)))
+}
 
 #[derive(Clone, Debug, PartialEq)]
 struct Board {
@@ -17,14 +46,25 @@ impl Board {
         Board { rows }
     }
 
-    fn from_string(s: &str) -> Board {
+    fn from_string(s: &str) -> Result<Board, ParseError<BingoError>> {
         let mut grid = [[0; 5]; 5];
-        for (ix, line) in s.lines().enumerate() {
-            for jx in 0..5 {
-                grid[ix][jx] = line[(jx * 3)..(jx * 3 + 2)].trim().parse().unwrap();
+        let mut lines = s.lines();
+        for (ix, row) in grid.iter_mut().enumerate() {
+            let at = |column| ParseError::new(ix + 1, column, BingoError::BadBoardSize);
+            let line = lines.next().ok_or_else(|| at(1))?;
+            let mut numbers = line.split_whitespace();
+            for cell in row.iter_mut() {
+                let number = numbers.next().ok_or_else(|| at(line.len() + 1))?;
+                *cell = parse_number(line, number).map_err(|err| err.offset_by(ix))?;
             }
+            if let Some(extra) = numbers.next() {
+                return Err(at(column_of(line, extra)));
+            }
+        }
+        if lines.next().is_some() {
+            return Err(ParseError::new(6, 1, BingoError::BadBoardSize));
         }
-        Board::from_grid(&grid)
+        Ok(Board::from_grid(&grid))
     }
 
     fn mark(&mut self, num: u8) -> bool {
@@ -54,36 +94,64 @@ impl Board {
     }
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle4");
-    let mut parts = input.split("\n\n");
-    let draws = parts
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|s| s.parse::<u8>().unwrap());
-    let mut boards: Vec<_> = parts.map(Board::from_string).collect();
-    let mut score_of_last_winning = None;
-    for draw in draws {
-        for board in &mut boards {
-            let already_won = board.has_won();
-            if board.mark(draw) {
-                if is_part2 {
-                    if !already_won {
-                        score_of_last_winning = Some(board.sum_unmarked() * draw as u32);
+pub struct Puzzle {
+    draws: Vec<u8>,
+    boards: Vec<Board>,
+}
+
+impl Puzzle {
+    fn play(&self, is_part2: bool) -> Option<u32> {
+        let mut boards = self.boards.clone();
+        let mut score_of_last_winning = None;
+        for &draw in &self.draws {
+            for board in &mut boards {
+                let already_won = board.has_won();
+                if board.mark(draw) {
+                    if is_part2 {
+                        if !already_won {
+                            score_of_last_winning = Some(board.sum_unmarked() * draw as u32);
+                        }
+                    } else {
+                        return Some(board.sum_unmarked() * draw as u32);
                     }
-                } else {
-                    println!("{}", board.sum_unmarked() * draw as u32);
-                    return;
                 }
             }
+            if is_part2 && boards.iter().all(Board::has_won) {
+                return score_of_last_winning;
+            }
         }
-        if is_part2 && boards.iter().all(Board::has_won) {
-            println!("{}", score_of_last_winning.unwrap());
-            return;
-        }
+        None // no board won
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = BingoError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<BingoError>> {
+        let mut parts = common::input::blocks(input);
+        let draws_line = parts
+            .next()
+            .ok_or_else(|| ParseError::new(1, 1, BingoError::MissingDraws))?;
+        let draws = draws_line
+            .split(',')
+            .map(|s| parse_number(draws_line, s))
+            .collect::<Result<_, _>>()
+            .map_err(|err| err.offset_by(lines_before(input, draws_line)))?;
+        let boards = parts
+            .map(|part| {
+                Board::from_string(part).map_err(|err| err.offset_by(lines_before(input, part)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Puzzle { draws, boards })
+    }
+
+    fn part1(&self) -> Answer {
+        self.play(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.play(true).into()
     }
-    panic!("no board won");
 }
 
 #[cfg(test)]
@@ -142,9 +210,14 @@ fn board_from_string() {
  6 10  3 18  5
  1 12 20 15 19\
     "
-        ),
+        )
+        .unwrap(),
         Board::from_grid(&EXAMPLE_GRIDS[0])
     );
+    assert_eq!(
+        Board::from_string("1 2 3 4 5\n1 2 3 4 5\n1 2 3 4\n").err(),
+        Some(ParseError::new(3, 8, BingoError::BadBoardSize))
+    );
 }
 
 #[test]
@@ -155,8 +228,8 @@ fn part1_example() {
         Board::from_grid(&EXAMPLE_GRIDS[2]),
     ];
     for draw in [7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21] {
-        for ix in 0..3 {
-            assert!(!boards[ix].mark(draw));
+        for board in &mut boards {
+            assert!(!board.mark(draw));
         }
     }
     assert!(!boards[0].mark(24));
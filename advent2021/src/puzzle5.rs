@@ -1,13 +1,48 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use ndarray::prelude::*;
 use std::cmp::{max, min};
+use std::error::Error;
+use std::fmt;
+
+const GRID_SIZE: usize = 1000;
 
 type Pair = ((usize, usize), (usize, usize));
 
-fn read_input(input: &str) -> impl Iterator<Item = Pair> + '_ {
-    input.lines().map(|line| {
-        let (x1, y1, x2, y2) =
-            scan_fmt!(line, "{},{} -> {},{}", usize, usize, usize, usize).unwrap();
-        ((x1, y1), (x2, y2))
+#[derive(Debug, PartialEq)]
+pub enum VentError {
+    BadLine(String),
+    OffGrid(usize),
+    NotStraight,
+}
+
+impl fmt::Display for VentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VentError::BadLine(line) => write!(f, "Expected \"x1,y1 -> x2,y2\", got {:?}", line),
+            VentError::OffGrid(coord) => {
+                write!(f, "Coordinate {} is off the {}-wide grid", coord, GRID_SIZE)
+            }
+            VentError::NotStraight => {
+                write!(f, "Line is neither horizontal, vertical, nor diagonal")
+            }
+        }
+    }
+}
+
+impl Error for VentError {}
+
+fn read_input(input: &str) -> Result<Vec<Pair>, ParseError<VentError>> {
+    parse_lines(input, |line| {
+        let (x1, y1, x2, y2) = scan_fmt!(line, "{},{} -> {},{}", usize, usize, usize, usize)
+            .map_err(|_| ParseError::at_column(1, VentError::BadLine(line.into())))?;
+        if let Some(&coord) = [x1, y1, x2, y2].iter().find(|&&coord| coord >= GRID_SIZE) {
+            return Err(ParseError::at_column(1, VentError::OffGrid(coord)));
+        }
+        if x1 != x2 && y1 != y2 && max(x1, x2) - min(x1, x2) != max(y1, y2) - min(y1, y2) {
+            return Err(ParseError::at_column(1, VentError::NotStraight));
+        }
+        Ok(((x1, y1), (x2, y2)))
     })
 }
 
@@ -32,13 +67,37 @@ fn count_dangerous_areas(grid: &Array2<usize>) -> usize {
     grid.mapv(|x| if x > 1 { 1 } else { 0 }).sum()
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle5");
-    let mut grid = Array::zeros((1000, 1000));
-    read_input(input)
-        .filter(|((x1, y1), (x2, y2))| if is_part2 { true } else { x1 == x2 || y1 == y2 })
-        .for_each(|coords| draw_line(&mut grid, coords));
-    println!("{}", count_dangerous_areas(&grid));
+pub struct Puzzle {
+    lines: Vec<Pair>,
+}
+
+impl Puzzle {
+    fn dangerous_areas(&self, is_part2: bool) -> usize {
+        let mut grid = Array::zeros((GRID_SIZE, GRID_SIZE));
+        self.lines
+            .iter()
+            .filter(|((x1, y1), (x2, y2))| if is_part2 { true } else { x1 == x2 || y1 == y2 })
+            .for_each(|&coords| draw_line(&mut grid, coords));
+        count_dangerous_areas(&grid)
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = VentError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<VentError>> {
+        Ok(Puzzle {
+            lines: read_input(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.dangerous_areas(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.dangerous_areas(true).into()
+    }
 }
 
 #[cfg(test)]
@@ -68,7 +127,11 @@ fn parse_input() {
         3,4 -> 1,4\n\
         0,0 -> 8,8\n\
         5,5 -> 8,2\n";
-    assert_eq!(read_input(INPUT).collect::<Vec<Pair>>(), &EXAMPLE_COORDS);
+    assert_eq!(read_input(INPUT).unwrap(), &EXAMPLE_COORDS);
+    assert_eq!(
+        read_input("0,9 -> 5,9\n8,0 -> 0,7\n").err(),
+        Some(ParseError::new(2, 1, VentError::NotStraight))
+    );
 }
 
 #[test]
@@ -1,3 +1,9 @@
+use common::error::{column_of, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
 type Population = [usize; 9];
 
 fn init_population(fish_list: impl IntoIterator<Item = usize>) -> Population {
@@ -15,10 +21,57 @@ fn simulate_days(pop: Population, n_days: usize) -> usize {
         .sum::<usize>()
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle6");
-    let pop = init_population(input.split(',').map(|s| s.parse().unwrap()));
-    println!("{}", simulate_days(pop, if is_part2 { 256 } else { 80 }));
+#[derive(Debug, PartialEq)]
+pub enum FishError {
+    BadTimer(ParseIntError),
+    TimerOutOfRange(usize),
+}
+
+impl fmt::Display for FishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FishError::BadTimer(err) => write!(f, "Bad timer: {}", err),
+            FishError::TimerOutOfRange(timer) => {
+                write!(f, "Timer {} out of range, expected 0 to 8", timer)
+            }
+        }
+    }
+}
+
+impl Error for FishError {}
+
+pub struct Puzzle {
+    pop: Population,
+}
+
+impl Solution for Puzzle {
+    type Error = FishError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<FishError>> {
+        let fish_list = input
+            .trim_end()
+            .split(',')
+            .map(|s| {
+                let at = |kind| ParseError::at_column(column_of(input, s), kind);
+                match s.parse() {
+                    Ok(fish) if fish < 9 => Ok(fish),
+                    Ok(fish) => Err(at(FishError::TimerOutOfRange(fish))),
+                    Err(err) => Err(at(FishError::BadTimer(err))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Puzzle {
+            pop: init_population(fish_list),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        simulate_days(self.pop, 80).into()
+    }
+
+    fn part2(&self) -> Answer {
+        simulate_days(self.pop, 256).into()
+    }
 }
 
 #[cfg(test)]
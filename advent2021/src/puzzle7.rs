@@ -1,5 +1,25 @@
+use common::error::{column_of, ParseError};
+use common::solution::{Answer, Solution};
 use itertools::Itertools;
 use ndarray::prelude::*;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum CrabError {
+    BadPosition(ParseIntError),
+}
+
+impl fmt::Display for CrabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrabError::BadPosition(err) => write!(f, "Bad position: {}", err),
+        }
+    }
+}
+
+impl Error for CrabError {}
 
 fn fuel_cost(positions: &Array1<i32>, target: i32) -> i32 {
     (positions - target).mapv(i32::abs).sum()
@@ -20,25 +40,35 @@ fn minimize_fuel(positions: &Array1<i32>, cost_function: &dyn Fn(&Array1<i32>, i
     )
 }
 
-pub fn main(is_part2: bool) {
-    let input = include_str!("input/puzzle7");
-    let positions = Array::from(
-        input
+pub struct Puzzle {
+    positions: Array1<i32>,
+}
+
+impl Solution for Puzzle {
+    type Error = CrabError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<CrabError>> {
+        let positions = input
+            .trim_end()
             .split(',')
-            .map(|s| s.parse().unwrap())
-            .collect::<Vec<_>>(),
-    );
-    println!(
-        "{}",
-        minimize_fuel(
-            &positions,
-            if is_part2 {
-                &quadratic_fuel_cost
-            } else {
-                &fuel_cost
-            }
-        )
-    );
+            .map(|s| {
+                s.parse().map_err(|err| {
+                    ParseError::at_column(column_of(input, s), CrabError::BadPosition(err))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Puzzle {
+            positions: Array::from(positions),
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        minimize_fuel(&self.positions, &fuel_cost).into()
+    }
+
+    fn part2(&self) -> Answer {
+        minimize_fuel(&self.positions, &quadratic_fuel_cost).into()
+    }
 }
 
 #[test]
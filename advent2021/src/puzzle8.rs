@@ -1,5 +1,28 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use multimap::MultiMap;
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum NoteError {
+    BadEntry(String),
+}
+
+impl fmt::Display for NoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NoteError::BadEntry(line) => write!(
+                f,
+                "Expected 10 patterns, \"|\", and 4 output digits, all made of a to g, got {:?}",
+                line
+            ),
+        }
+    }
+}
+
+impl Error for NoteError {}
 
 fn process_signal(signal: &str) -> u8 {
     let mut retval = 0;
@@ -16,7 +39,7 @@ fn process_signal(signal: &str) -> u8 {
     retval
 }
 
-fn process_line(line: &str) -> ([u8; 10], [u8; 4]) {
+fn process_line(line: &str) -> Result<([u8; 10], [u8; 4]), ParseError<NoteError>> {
     let vals = scan_fmt!(
         line,
         &("{/[a-g]+/} ".repeat(10) + " | " + &"{/[a-g]+/} ".repeat(4)),
@@ -35,23 +58,20 @@ fn process_line(line: &str) -> ([u8; 10], [u8; 4]) {
         String,
         String
     )
-    .unwrap();
-    (
+    .map_err(|_| ParseError::at_column(1, NoteError::BadEntry(line.into())))?;
+    Ok((
         [
             vals.0, vals.1, vals.2, vals.3, vals.4, vals.5, vals.6, vals.7, vals.8, vals.9,
         ]
         .map(|s| process_signal(&s)),
         [vals.10, vals.11, vals.12, vals.13].map(|s| process_signal(&s)),
-    )
+    ))
 }
 
 fn count_unique_digits((_, output): ([u8; 10], [u8; 4])) -> usize {
     output
         .iter()
-        .filter(|v| match v.count_ones() {
-            2 | 3 | 4 | 7 => true,
-            _ => false,
-        })
+        .filter(|v| matches!(v.count_ones(), 2 | 3 | 4 | 7))
         .count()
 }
 
@@ -95,19 +115,34 @@ fn decode_digits((patterns, output): ([u8; 10], [u8; 4])) -> usize {
         + mapping[&output[3]]
 }
 
-pub fn main(is_part2: bool) {
-    println!(
-        "{}",
-        include_str!("input/puzzle8")
-            .lines()
-            .map(process_line)
-            .map(if is_part2 {
-                decode_digits
-            } else {
-                count_unique_digits
-            })
+pub struct Puzzle {
+    entries: Vec<([u8; 10], [u8; 4])>,
+}
+
+impl Solution for Puzzle {
+    type Error = NoteError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<NoteError>> {
+        Ok(Puzzle {
+            entries: parse_lines(input, process_line)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.entries
+            .iter()
+            .map(|&entry| count_unique_digits(entry))
             .sum::<usize>()
-    );
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.entries
+            .iter()
+            .map(|&entry| decode_digits(entry))
+            .sum::<usize>()
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -139,7 +174,7 @@ fn test_process_signal() {
 
 #[test]
 fn test_process_line() {
-    let (patterns, output) = process_line(EXAMPLE_LINE);
+    let (patterns, output) = process_line(EXAMPLE_LINE).unwrap();
     assert_eq!(
         patterns,
         [
@@ -152,7 +187,7 @@ fn test_process_line() {
 
 #[test]
 fn test_decode() {
-    let data = process_line(EXAMPLE_LINE);
+    let data = process_line(EXAMPLE_LINE).unwrap();
     assert_eq!(decode_digits(data), 5353);
 }
 
@@ -161,7 +196,7 @@ fn part1_example() {
     assert_eq!(
         EXAMPLE_INPUT
             .lines()
-            .map(process_line)
+            .map(|line| process_line(line).unwrap())
             .map(count_unique_digits)
             .sum::<usize>(),
         26
@@ -173,7 +208,7 @@ fn part2_example() {
     assert_eq!(
         EXAMPLE_INPUT
             .lines()
-            .map(process_line)
+            .map(|line| process_line(line).unwrap())
             .map(decode_digits)
             .sum::<usize>(),
         61229
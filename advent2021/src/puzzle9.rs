@@ -1,26 +1,54 @@
+use automaton::render::{Draw, Frame, Layout, Palette, Renderer};
+use common::error::ParseError;
+use common::solution::{Answer, Solution};
 use ndarray::prelude::*;
+use std::error::Error;
+use std::fmt;
 
-fn parse(s: &str) -> Array2<u8> {
+#[derive(Debug, PartialEq)]
+pub enum HeightmapError {
+    Empty,
+    BadHeight(char),
+    RaggedRow { expected: usize, got: usize },
+}
+
+impl fmt::Display for HeightmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeightmapError::Empty => write!(f, "Heightmap is empty"),
+            HeightmapError::BadHeight(c) => write!(f, "Bad height {:?}, expected a digit", c),
+            HeightmapError::RaggedRow { expected, got } => {
+                write!(f, "Row is {} wide, but the first row is {}", got, expected)
+            }
+        }
+    }
+}
+
+impl Error for HeightmapError {}
+
+fn parse(s: &str) -> Result<Array2<u8>, ParseError<HeightmapError>> {
     let lines: Vec<&str> = s.lines().collect();
-    let mut result = Array2::zeros((lines.len(), lines[0].len()));
+    let width = match lines.first() {
+        Some(line) if !line.is_empty() => line.len(),
+        _ => return Err(ParseError::new(1, 1, HeightmapError::Empty)),
+    };
+    let mut result = Array2::zeros((lines.len(), width));
     for (i, line) in lines.iter().enumerate() {
-        for (j, b) in line.bytes().enumerate() {
-            result[(i, j)] = match b {
-                b'0' => 0,
-                b'1' => 1,
-                b'2' => 2,
-                b'3' => 3,
-                b'4' => 4,
-                b'5' => 5,
-                b'6' => 6,
-                b'7' => 7,
-                b'8' => 8,
-                b'9' => 9,
-                _ => panic!("Unexpected input {}", b),
-            }
+        if line.len() != width {
+            let kind = HeightmapError::RaggedRow {
+                expected: width,
+                got: line.len(),
+            };
+            return Err(ParseError::new(i + 1, 1, kind));
+        }
+        for (j, c) in line.chars().enumerate() {
+            result[(i, j)] = c
+                .to_digit(10)
+                .ok_or_else(|| ParseError::new(i + 1, j + 1, HeightmapError::BadHeight(c)))?
+                as u8;
         }
     }
-    result
+    Ok(result)
 }
 
 fn low_points(arr: &Array2<u8>) -> Vec<(usize, usize)> {
@@ -51,7 +79,7 @@ fn low_points(arr: &Array2<u8>) -> Vec<(usize, usize)> {
 }
 
 fn risk_levels(arr: &Array2<u8>) -> Vec<usize> {
-    low_points(&arr)
+    low_points(arr)
         .iter()
         .map(|&index| (arr[index] + 1) as usize)
         .collect()
@@ -77,7 +105,7 @@ fn flood_fill(basin: &mut Array2<usize>, arr: &Array2<u8>, (row, col): (usize, u
 }
 
 fn basins(arr: &Array2<u8>) -> Vec<usize> {
-    low_points(&arr)
+    low_points(arr)
         .iter()
         .map(|&index| {
             let mut basin = Array2::<usize>::zeros(arr.raw_dim());
@@ -87,12 +115,77 @@ fn basins(arr: &Array2<u8>) -> Vec<usize> {
         .collect()
 }
 
-pub fn main() {
-    let grid = parse(include_str!("input/puzzle9"));
-    println!("{}", risk_levels(&grid).iter().sum::<usize>());
-    let mut basins = basins(&grid);
-    basins.sort();
-    println!("{}", basins.iter().rev().take(3).product::<usize>());
+pub struct Puzzle {
+    grid: Array2<u8>,
+}
+
+impl Solution for Puzzle {
+    type Error = HeightmapError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<HeightmapError>> {
+        Ok(Puzzle {
+            grid: parse(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        risk_levels(&self.grid).iter().sum::<usize>().into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut basins = basins(&self.grid);
+        basins.sort();
+        basins.iter().rev().take(3).product::<usize>().into()
+    }
+}
+
+// Values in the frames, after the heights 0 to 9
+const LOW_POINT: u8 = 10;
+const FLOODED: u8 = 11;
+
+// Part 1 shows the low points on the heightmap. Part 2 shows the basins
+// filling up from them, one step further each frame.
+impl Draw for Puzzle {
+    fn renderer(&self) -> Renderer {
+        let mut palette: Vec<_> = (0..=9)
+            .map(|height| {
+                let grey = 0x30 + height * 0x14;
+                (char::from(b'0' + height), [grey, grey, grey])
+            })
+            .collect();
+        palette.push(('*', [0xe0, 0x30, 0x30]));
+        palette.push(('~', [0x30, 0x60, 0xe0]));
+        Renderer::new(Layout::Square).with_palette(Palette::new(palette))
+    }
+
+    fn frames(&self, is_part2: bool) -> Vec<Frame> {
+        let mut map = self.grid.clone();
+        let mut edge = low_points(&self.grid);
+        if !is_part2 {
+            edge.iter().for_each(|&index| map[index] = LOW_POINT);
+            return vec![Frame::new(map)];
+        }
+        let mut frames = vec![];
+        while !edge.is_empty() {
+            edge.iter().for_each(|&index| map[index] = FLOODED);
+            frames.push(Frame::new(map.clone()));
+            edge = edge
+                .iter()
+                .flat_map(|&(row, col)| {
+                    [
+                        (row.wrapping_sub(1), col),
+                        (row + 1, col),
+                        (row, col.wrapping_sub(1)),
+                        (row, col + 1),
+                    ]
+                })
+                .filter(|&index| map.get(index).is_some_and(|&height| height < 9))
+                .collect();
+            edge.sort_unstable();
+            edge.dedup();
+        }
+        frames
+    }
 }
 
 #[cfg(test)]
@@ -117,9 +210,14 @@ fn test_parse() {
 8767896789
 9899965678
 "
-        ),
+        )
+        .unwrap(),
         gen_example()
     );
+    assert_eq!(
+        parse("2199\n39a7\n").err(),
+        Some(ParseError::new(2, 3, HeightmapError::BadHeight('a')))
+    );
 }
 
 #[test]
@@ -166,3 +264,21 @@ fn part2_example() {
     basins.sort();
     assert_eq!(basins.iter().rev().take(3).product::<usize>(), 1134);
 }
+
+#[test]
+fn test_draw() {
+    let puzzle = Puzzle {
+        grid: gen_example(),
+    };
+    let renderer = puzzle.renderer();
+    let frames = puzzle.frames(false);
+    assert_eq!(
+        renderer.ascii(&frames[0]).lines().next(),
+        Some("2*9994321*")
+    );
+    let frames = puzzle.frames(true);
+    let flooded = frames.last().unwrap();
+    let basins = flooded.cells.iter().filter(|&&v| v == FLOODED).count();
+    assert_eq!(basins, 3 + 9 + 14 + 9);
+    assert_eq!(renderer.ascii(flooded).lines().next(), Some("~~999~~~~~"));
+}
@@ -0,0 +1,367 @@
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::Rule;
+use ndarray::{ArrayD, Axis, Slice, Zip};
+
+// A grid of live (1) and dead (0) cells, with the rule that takes it from one
+// generation to the next. Without a mask, the grid is unbounded: it grows by
+// one cell on a side whenever a live cell reaches that edge, so it can run for
+// any number of generations. (Except with a rule giving birth at 0
+// neighbours, which would fill infinite space; then, as with a mask, cells
+// beyond the edges of the grid stay dead.)
+#[derive(Clone, Debug)]
+pub struct Automaton {
+    cells: ArrayD<u8>,
+    // Coordinates of the first cell of the grid, relative to where it started
+    origin: Vec<isize>,
+    mask: Option<ArrayD<u8>>,
+    neighbourhood: Neighbourhood,
+    offsets: Vec<Vec<isize>>,
+    table: [Vec<u8>; 2],
+    generation: usize,
+    // For LineOfSight, who sees whom, for the grid shape it was built for
+    sight: Option<SightGraph>,
+}
+
+// The cells each cell can see, in compressed sparse row form: the flat indices
+// of the cells seen from cell i are targets[starts[i]..starts[i + 1]]. The
+// view only depends on the mask, so this is built once instead of looking
+// outward from every cell in every generation.
+#[derive(Clone, Debug)]
+struct SightGraph {
+    shape: Vec<usize>,
+    starts: Vec<u32>,
+    targets: Vec<u32>,
+}
+
+impl Automaton {
+    pub fn new(cells: ArrayD<u8>, neighbourhood: Neighbourhood, rule: &Rule) -> Self {
+        let offsets = neighbourhood.offsets(cells.ndim());
+        let table = rule.table(offsets.len());
+        Automaton {
+            origin: vec![0; cells.ndim()],
+            cells,
+            mask: None,
+            neighbourhood,
+            offsets,
+            table,
+            generation: 0,
+            sight: None,
+        }
+    }
+
+    // Only cells where the mask is 1 can ever be alive. The mask is also what
+    // blocks the view for the LineOfSight neighbourhood.
+    pub fn with_mask(mut self, mask: ArrayD<u8>) -> Self {
+        assert_eq!(mask.shape(), self.cells.shape());
+        self.cells *= &mask;
+        self.mask = Some(mask);
+        self.sight = None;
+        self
+    }
+
+    pub fn cells(&self) -> &ArrayD<u8> {
+        &self.cells
+    }
+
+    pub fn origin(&self) -> &[isize] {
+        &self.origin
+    }
+
+    // The cell at the given coordinates, which can be anywhere
+    pub fn get(&self, coordinates: &[isize]) -> u8 {
+        let index: Option<Vec<usize>> = coordinates
+            .iter()
+            .zip(&self.origin)
+            .map(|(x, origin)| usize::try_from(x - origin).ok())
+            .collect();
+        index
+            .and_then(|index| self.cells.get(index.as_slice()).copied())
+            .unwrap_or(0)
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell != 0).count()
+    }
+
+    pub fn neighbour_counts(&self) -> ArrayD<u16> {
+        match self.neighbourhood {
+            Neighbourhood::LineOfSight => match &self.sight {
+                Some(graph) if graph.shape == self.cells.shape() => self.count_in_sight(graph),
+                _ => self.count_in_sight(&self.sight_graph()),
+            },
+            _ => self.count_adjacent(),
+        }
+    }
+
+    // Adds up copies of the grid shifted by each offset
+    fn count_adjacent(&self) -> ArrayD<u16> {
+        let cells = self.cells.mapv(u16::from);
+        let mut counts = ArrayD::zeros(cells.raw_dim());
+        for offset in &self.offsets {
+            let mut dest = counts.slice_each_axis_mut(|axis| {
+                let (len, delta) = (axis.len as isize, offset[axis.axis.index()]);
+                Slice::from(delta.max(0)..(len + delta).min(len))
+            });
+            dest += &cells.slice_each_axis(|axis| {
+                let (len, delta) = (axis.len as isize, offset[axis.axis.index()]);
+                Slice::from((-delta).max(0)..(len - delta).min(len))
+            });
+        }
+        counts
+    }
+
+    fn count_in_sight(&self, graph: &SightGraph) -> ArrayD<u16> {
+        let cells = self.cells.as_standard_layout();
+        let cells = cells.as_slice().unwrap();
+        let counts = graph
+            .starts
+            .windows(2)
+            .map(|range| {
+                graph.targets[range[0] as usize..range[1] as usize]
+                    .iter()
+                    .map(|&seen| u16::from(cells[seen as usize]))
+                    .sum()
+            })
+            .collect();
+        ArrayD::from_shape_vec(self.cells.shape(), counts).unwrap()
+    }
+
+    // Looks outward from every cell in the mask, in each direction, for the
+    // nearest other cell in the mask. Works on the flat, row-major cells, so
+    // a step in a direction is a fixed change of the flat index.
+    fn sight_graph(&self) -> SightGraph {
+        let shape = self.cells.shape();
+        let mask = self.mask.as_ref().map(|mask| mask.as_standard_layout());
+        let mask = mask.as_ref().map(|mask| mask.as_slice().unwrap());
+        let in_mask = |ix: usize| mask.is_none_or(|mask| mask[ix] != 0);
+
+        let mut strides = vec![1; shape.len()];
+        for axis in (0..shape.len().saturating_sub(1)).rev() {
+            strides[axis] = strides[axis + 1] * shape[axis + 1] as isize;
+        }
+        let steps: Vec<isize> = self
+            .offsets
+            .iter()
+            .map(|direction| direction.iter().zip(&strides).map(|(d, s)| d * s).sum())
+            .collect();
+
+        let n_cells = self.cells.len();
+        assert!(
+            n_cells < u32::MAX as usize,
+            "grid too large for line of sight"
+        );
+        let mut starts = Vec::with_capacity(n_cells + 1);
+        let mut targets = vec![];
+        let mut start = vec![0; shape.len()];
+        let mut position = vec![0; shape.len()];
+        for ix in 0..n_cells {
+            starts.push(targets.len() as u32);
+            if !in_mask(ix) {
+                continue;
+            }
+            let mut rest = ix;
+            for (axis, coordinate) in start.iter_mut().enumerate().rev() {
+                *coordinate = (rest % shape[axis]) as isize;
+                rest /= shape[axis];
+            }
+            for (direction, &step) in self.offsets.iter().zip(&steps) {
+                position.copy_from_slice(&start);
+                let mut seen = ix as isize;
+                loop {
+                    let mut inside = true;
+                    for ((x, delta), &len) in position.iter_mut().zip(direction).zip(shape) {
+                        *x += delta;
+                        inside &= *x >= 0 && *x < len as isize;
+                    }
+                    if !inside {
+                        break;
+                    }
+                    seen += step;
+                    if in_mask(seen as usize) {
+                        targets.push(seen as u32);
+                        break;
+                    }
+                }
+            }
+        }
+        starts.push(targets.len() as u32);
+        SightGraph {
+            shape: shape.to_vec(),
+            starts,
+            targets,
+        }
+    }
+
+    fn is_unbounded(&self) -> bool {
+        self.mask.is_none() && self.table[0][0] == 0
+    }
+
+    // Pads the grid by one cell on each side where a live cell touches the
+    // edge, which is as far as any neighbourhood but LineOfSight reaches, so
+    // the next generation has room
+    fn grow(&mut self) {
+        let shape = self.cells.shape();
+        let has_live = |axis, ix| {
+            self.cells
+                .index_axis(Axis(axis), ix)
+                .iter()
+                .any(|&cell| cell != 0)
+        };
+        let (before, after): (Vec<_>, Vec<_>) = shape
+            .iter()
+            .enumerate()
+            .map(|(axis, &len)| match len {
+                0 => (0, 0),
+                _ => (has_live(axis, 0) as usize, has_live(axis, len - 1) as usize),
+            })
+            .unzip();
+        if before.iter().chain(&after).all(|&pad| pad == 0) {
+            return;
+        }
+        let new_shape: Vec<_> = (0..shape.len())
+            .map(|axis| before[axis] + shape[axis] + after[axis])
+            .collect();
+        let mut cells = ArrayD::zeros(new_shape);
+        cells
+            .slice_each_axis_mut(|axis| {
+                let start = before[axis.axis.index()];
+                Slice::from(start..start + shape[axis.axis.index()])
+            })
+            .assign(&self.cells);
+        for (origin, pad) in self.origin.iter_mut().zip(before) {
+            *origin -= pad as isize;
+        }
+        self.cells = cells;
+    }
+
+    // Advances one generation. Returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        if self.is_unbounded() {
+            self.grow();
+        }
+        if self.neighbourhood == Neighbourhood::LineOfSight
+            && self
+                .sight
+                .as_ref()
+                .is_none_or(|graph| graph.shape != self.cells.shape())
+        {
+            self.sight = Some(self.sight_graph());
+        }
+        let counts = self.neighbour_counts();
+        let mut next = ArrayD::zeros(self.cells.raw_dim());
+        Zip::from(&mut next)
+            .and(&self.cells)
+            .and(&counts)
+            .for_each(|next, &cell, &count| {
+                *next = self.table[(cell != 0) as usize][count as usize];
+            });
+        if let Some(mask) = &self.mask {
+            next *= mask;
+        }
+        self.generation += 1;
+        let changed = next != self.cells;
+        self.cells = next;
+        changed
+    }
+
+    // Iterates over the following generations, forever
+    pub fn generations(&mut self) -> Generations<'_> {
+        Generations { automaton: self }
+    }
+}
+
+pub struct Generations<'a> {
+    automaton: &'a mut Automaton,
+}
+
+impl Iterator for Generations<'_> {
+    type Item = ArrayD<u8>;
+
+    fn next(&mut self) -> Option<ArrayD<u8>> {
+        self.automaton.step();
+        Some(self.automaton.cells.clone())
+    }
+}
+
+#[cfg(test)]
+fn grid(rows: &[&str]) -> ArrayD<u8> {
+    let cells: Vec<_> = rows
+        .iter()
+        .flat_map(|row| row.chars().map(|c| (c == '#') as u8))
+        .collect();
+    ArrayD::from_shape_vec(vec![rows.len(), rows[0].len()], cells).unwrap()
+}
+
+#[test]
+fn test_blinker() {
+    let start = grid(&[".....", "..#..", "..#..", "..#..", "....."]);
+    let mut automaton = Automaton::new(start.clone(), Neighbourhood::Moore, &Rule::life());
+    let states: Vec<_> = automaton.generations().take(2).collect();
+    assert_eq!(
+        states[0],
+        grid(&[".....", ".....", ".###.", ".....", "....."])
+    );
+    assert_eq!(states[1], start);
+    assert_eq!((automaton.generation(), automaton.population()), (2, 3));
+}
+
+#[test]
+fn test_von_neumann() {
+    let start = grid(&["...", ".#.", "..."]);
+    let mut automaton = Automaton::new(start, Neighbourhood::VonNeumann, &Rule::new([1], []));
+    assert!(automaton.step());
+    assert_eq!(*automaton.cells(), grid(&[".#.", "#.#", ".#."]));
+}
+
+#[test]
+fn test_line_of_sight() {
+    let mask = grid(&["#.#..#", "......", "#...#."]);
+    let cells = grid(&["#.#..#", "......", "#....."]);
+    let automaton =
+        Automaton::new(cells, Neighbourhood::LineOfSight, &Rule::life()).with_mask(mask);
+    let counts = automaton.neighbour_counts();
+    assert_eq!(counts[[0, 0].as_slice()], 2);
+    assert_eq!(counts[[0, 2].as_slice()], 3);
+    assert_eq!(counts[[2, 4].as_slice()], 2);
+    assert_eq!(counts[[1, 1].as_slice()], 0);
+}
+
+// Without a mask, the nearest cell in sight is the adjacent one, even as the
+// grid grows and the sight graph has to be rebuilt
+#[test]
+fn test_line_of_sight_unmasked() {
+    let start = grid(&[".#.", "..#", "###"]);
+    let mut moore = Automaton::new(start.clone(), Neighbourhood::Moore, &Rule::life());
+    let mut sight = Automaton::new(start, Neighbourhood::LineOfSight, &Rule::life());
+    for _ in 0..8 {
+        moore.step();
+        sight.step();
+        assert_eq!(sight.cells(), moore.cells());
+    }
+}
+
+// A glider keeps going well past the edges of the grid it started in
+#[test]
+fn test_growth() {
+    let start = grid(&[".#.", "..#", "###"]);
+    let mut automaton = Automaton::new(start, Neighbourhood::Moore, &Rule::life());
+    automaton.generations().nth(39);
+    assert_eq!(automaton.population(), 5);
+    assert_eq!(automaton.origin(), [-1, -1]);
+    assert_eq!(automaton.get(&[12, 10]), 1);
+    assert_eq!(automaton.get(&[-5, 100]), 0);
+}
+
+// With birth at 0 neighbours, the grid stays as it is
+#[test]
+fn test_bounded() {
+    let start = grid(&["#..", "...", "..."]);
+    let mut automaton = Automaton::new(start, Neighbourhood::Moore, &Rule::new([0], []));
+    automaton.step();
+    assert_eq!(*automaton.cells(), grid(&["..#", "..#", "###"]));
+    assert_eq!(automaton.origin(), [0, 0]);
+}
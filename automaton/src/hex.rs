@@ -0,0 +1,285 @@
+// Coordinates on a grid of pointy-topped hexagons. A Hex is stored in axial
+// coordinates: q increases to the east and r to the south-east. The third
+// cube coordinate, s, is -q - r, so q + r + s = 0 always. As grid indices,
+// [r, q] are the axes that Neighbourhood::Hex and render::Layout::Hex expect.
+//
+// Offset coordinates number the cells in each row from the left, with odd
+// rows shoved right by half a cell ("odd-r").
+
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl Direction {
+    // In clockwise order, starting from the east
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::SouthEast,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+        Direction::NorthEast,
+    ];
+
+    // The Hex one step away from the origin in this direction
+    pub fn unit(self) -> Hex {
+        match self {
+            Direction::East => Hex::new(1, 0),
+            Direction::SouthEast => Hex::new(0, 1),
+            Direction::SouthWest => Hex::new(-1, 1),
+            Direction::West => Hex::new(-1, 0),
+            Direction::NorthWest => Hex::new(0, -1),
+            Direction::NorthEast => Hex::new(1, -1),
+        }
+    }
+
+    // The short name, as in "e" or "nw"
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::East => "e",
+            Direction::SouthEast => "se",
+            Direction::SouthWest => "sw",
+            Direction::West => "w",
+            Direction::NorthWest => "nw",
+            Direction::NorthEast => "ne",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|direction| direction.name() == name)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.turn(3)
+    }
+
+    // Turns clockwise by `sixths` of a full turn; negative for anticlockwise
+    pub fn turn(self, sixths: i32) -> Self {
+        Direction::ALL[(self as i32 + sixths).rem_euclid(6) as usize]
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Hex { q, r }
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    // Panics unless the coordinates add up to 0
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        assert_eq!(q + r + s, 0, "cube coordinates must add up to 0");
+        Hex { q, r }
+    }
+
+    pub fn cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn from_offset(column: i32, row: i32) -> Self {
+        Hex::new(column - (row - (row & 1)) / 2, row)
+    }
+
+    // (column, row)
+    pub fn offset(self) -> (i32, i32) {
+        (self.q + (self.r - (self.r & 1)) / 2, self.r)
+    }
+
+    pub fn neighbour(self, direction: Direction) -> Self {
+        self + direction.unit()
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = Hex> {
+        Direction::ALL
+            .into_iter()
+            .map(move |dir| self.neighbour(dir))
+    }
+
+    // The number of steps to the origin
+    pub fn length(self) -> i32 {
+        self.q.abs().max(self.r.abs()).max(self.s().abs())
+    }
+
+    pub fn distance(self, other: Hex) -> i32 {
+        (self - other).length()
+    }
+
+    // The hexes at exactly `radius` steps, clockwise from the north-west
+    // corner; just this one for radius 0
+    pub fn ring(self, radius: i32) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut hex = self + Direction::NorthWest.unit() * radius;
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        for direction in Direction::ALL {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex = hex.neighbour(direction);
+            }
+        }
+        ring
+    }
+
+    // The hexes at most `radius` steps away, ring by ring outwards
+    pub fn spiral(self, radius: i32) -> Vec<Hex> {
+        (0..=radius).flat_map(|ring| self.ring(ring)).collect()
+    }
+
+    // Rotates about the origin clockwise by `sixths` of a full turn; negative
+    // for anticlockwise
+    pub fn rotate(self, sixths: i32) -> Self {
+        (0..sixths.rem_euclid(6)).fold(self, |hex, _| {
+            let (q, r, s) = hex.cube();
+            Hex::from_cube(-r, -s, -q)
+        })
+    }
+
+    pub fn rotate_about(self, center: Hex, sixths: i32) -> Self {
+        center + (self - center).rotate(sixths)
+    }
+
+    // Mirrors across the line through the origin where q stays the same,
+    // swapping r and s
+    pub fn reflect_q(self) -> Self {
+        let (q, r, s) = self.cube();
+        Hex::from_cube(q, s, r)
+    }
+
+    pub fn reflect_r(self) -> Self {
+        let (q, r, s) = self.cube();
+        Hex::from_cube(s, r, q)
+    }
+
+    pub fn reflect_s(self) -> Self {
+        let (q, r, s) = self.cube();
+        Hex::from_cube(r, q, s)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, factor: i32) -> Hex {
+        Hex::new(self.q * factor, self.r * factor)
+    }
+}
+
+#[test]
+fn test_directions() {
+    for direction in Direction::ALL {
+        assert_eq!(direction.unit().length(), 1);
+        assert_eq!(direction.opposite().unit(), -direction.unit());
+        assert_eq!(direction.unit().rotate(1), direction.turn(1).unit());
+        assert_eq!(Direction::from_name(direction.name()), Some(direction));
+    }
+    assert_eq!(Direction::East.turn(-1), Direction::NorthEast);
+    assert_eq!(Direction::from_name("n"), None);
+}
+
+#[test]
+fn test_distance() {
+    let a = Hex::new(3, -1);
+    assert_eq!(a.cube(), (3, -1, -2));
+    assert_eq!(a.length(), 3);
+    assert_eq!(a.distance(Hex::new(-1, 2)), 4);
+    assert!(a.neighbours().all(|hex| hex.distance(a) == 1));
+}
+
+#[test]
+fn test_offset() {
+    assert_eq!(Hex::new(-1, 1).offset(), (-1, 1));
+    assert_eq!(Hex::new(-1, 2).offset(), (0, 2));
+    assert_eq!(Hex::new(0, -1).offset(), (-1, -1));
+    for hex in Hex::new(2, -3).spiral(3) {
+        let (column, row) = hex.offset();
+        assert_eq!(Hex::from_offset(column, row), hex);
+    }
+}
+
+#[test]
+fn test_rings() {
+    let center = Hex::new(5, 5);
+    assert_eq!(center.ring(0), [center]);
+    for radius in 1..5 {
+        let ring = center.ring(radius);
+        assert_eq!(ring.len(), 6 * radius as usize);
+        assert!(ring.iter().all(|hex| hex.distance(center) == radius));
+        assert_eq!(ring[0].distance(*ring.last().unwrap()), 1);
+    }
+    let mut spiral = center.spiral(3);
+    assert_eq!(spiral.len(), 37);
+    spiral.sort();
+    spiral.dedup();
+    assert_eq!(spiral.len(), 37);
+}
+
+#[test]
+fn test_symmetries() {
+    let hex = Hex::new(2, -5);
+    assert_eq!(hex.rotate(6), hex);
+    assert_eq!(hex.rotate(-2), hex.rotate(4));
+    assert_eq!(hex.rotate(3), -hex);
+    assert_eq!(hex.rotate(1).length(), hex.length());
+    let center = Hex::new(1, 1);
+    assert_eq!(center.rotate_about(center, 2), center);
+    assert_eq!(hex.rotate_about(center, 3), center * 2 - hex);
+    for reflect in [Hex::reflect_q, Hex::reflect_r, Hex::reflect_s] {
+        assert_eq!(reflect(reflect(hex)), hex);
+        assert_eq!(reflect(hex).length(), hex.length());
+    }
+    assert_eq!(hex.reflect_q(), Hex::new(2, 3));
+    assert_eq!(Direction::East.unit().reflect_r(), Direction::West.unit());
+}
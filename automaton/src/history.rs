@@ -0,0 +1,101 @@
+// Runs an automaton until it repeats a state, remembering every generation's
+// state by hash, so that oscillators are found as well as still lifes.
+
+use crate::engine::Automaton;
+use ndarray::ArrayD;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    // The first generation that repeats
+    pub start: usize,
+    // 1 for a state that no longer changes
+    pub period: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    // The population of each distinct generation, counting from where the
+    // automaton was when the run started
+    pub populations: Vec<usize>,
+    // None if the run stopped before the automaton repeated itself
+    pub cycle: Option<Cycle>,
+}
+
+impl Summary {
+    // The generation from which nothing changes any more
+    pub fn stable_from(&self) -> Option<usize> {
+        self.cycle
+            .filter(|cycle| cycle.period == 1)
+            .map(|cycle| cycle.start)
+    }
+
+    pub fn stable_population(&self) -> Option<usize> {
+        self.stable_from()
+            .map(|generation| self.populations[generation])
+    }
+}
+
+// Steps until a state repeats, or for at most `max_generations` steps.
+// States include the grid's origin, so a pattern that moves isn't a cycle.
+pub fn run(automaton: &mut Automaton, max_generations: usize) -> Summary {
+    let mut seen: HashMap<(Vec<isize>, ArrayD<u8>), usize> = HashMap::new();
+    let mut populations = vec![];
+    for generation in 0..=max_generations {
+        let state = (automaton.origin().to_vec(), automaton.cells().clone());
+        if let Some(start) = seen.insert(state, generation) {
+            let period = generation - start;
+            return Summary {
+                populations,
+                cycle: Some(Cycle { start, period }),
+            };
+        }
+        populations.push(automaton.population());
+        automaton.step();
+    }
+    Summary {
+        populations,
+        cycle: None,
+    }
+}
+
+#[cfg(test)]
+use crate::neighbourhood::Neighbourhood;
+#[cfg(test)]
+use crate::rule::Rule;
+
+#[cfg(test)]
+fn life(rows: &[&str]) -> Automaton {
+    let cells: Vec<_> = rows
+        .iter()
+        .flat_map(|row| row.chars().map(|c| (c == '#') as u8))
+        .collect();
+    let grid = ArrayD::from_shape_vec(vec![rows.len(), rows[0].len()], cells).unwrap();
+    Automaton::new(grid, Neighbourhood::Moore, &Rule::life())
+}
+
+#[test]
+fn test_cycles() {
+    let mut blinker = life(&[".....", "..#..", "..#..", "..#..", "....."]);
+    let summary = run(&mut blinker, 100);
+    assert_eq!(
+        summary.cycle,
+        Some(Cycle {
+            start: 0,
+            period: 2
+        })
+    );
+    assert_eq!(summary.populations, [3, 3]);
+    assert_eq!(summary.stable_from(), None);
+
+    let mut block = life(&["#....", ".....", "..##.", "..##.", "....."]);
+    let summary = run(&mut block, 100);
+    assert_eq!(summary.stable_from(), Some(1));
+    assert_eq!(summary.populations, [5, 4]);
+    assert_eq!(summary.stable_population(), Some(4));
+
+    let mut glider = life(&[".#.", "..#", "###"]);
+    let summary = run(&mut glider, 20);
+    assert_eq!(summary.cycle, None);
+    assert_eq!(summary.populations.len(), 21);
+}
@@ -0,0 +1,36 @@
+//! Conway-style cellular automata on grids of any number of dimensions,
+//! shared by 2020 days 11, 17 and 24.
+//!
+//! An `engine::Automaton` holds a dense grid, a `Neighbourhood` (Moore, von
+//! Neumann, hex, or line of sight), and a birth/survival `Rule`. `step()`
+//! advances one generation and `generations()` iterates over them. Unless
+//! cells are restricted to a mask, as the seats are on day 11, the grid grows
+//! whenever a live cell reaches its edge. With the line of sight
+//! neighbourhood, which cells each cell can see is worked out once and kept
+//! for as long as the grid keeps its shape.
+//!
+//! `sparse::SparseAutomaton` only stores live cells, and starts from a 2D
+//! slice, so the state stays symmetric in the other axes. It stores one cell
+//! for each family of mirrored or permuted cells, which keeps five or six
+//! dimensions cheap.
+//!
+//! `history::run` steps an automaton until a state repeats, and returns a
+//! `Summary` with the population of each generation, and the generation where
+//! the cycle starts and its period. A period of 1 means the automaton has
+//! stabilised.
+//!
+//! `hex` has coordinates for hex grids: a `Hex` in axial coordinates, with
+//! conversions to cube and offset coordinates, distances, rings and spirals,
+//! rotations and reflections, and the six `Direction`s.
+//! `Neighbourhood::Hex` takes its offsets from it.
+//!
+//! `render::Renderer` draws `Frame`s of a grid as ASCII art, PNG images or an
+//! animated GIF, and `render::Draw` is what a puzzle implements to be drawn.
+
+pub mod engine;
+pub mod hex;
+pub mod history;
+pub mod neighbourhood;
+pub mod render;
+pub mod rule;
+pub mod sparse;
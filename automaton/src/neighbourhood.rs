@@ -0,0 +1,61 @@
+use crate::hex::Direction;
+use itertools::Itertools;
+use std::iter;
+
+// Which cells count as neighbours of a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    // Every cell at most one step away along each axis: 8 in 2D, 26 in 3D
+    Moore,
+    // Every cell one step away along one axis: 4 in 2D, 6 in 3D
+    VonNeumann,
+    // The six neighbours of a hexagon, in 2D axial coordinates [r, q] as in
+    // the hex module
+    Hex,
+    // The nearest cell in the automaton's mask in each Moore direction,
+    // however far away it is
+    LineOfSight,
+}
+
+impl Neighbourhood {
+    // The offsets from a cell to its neighbours. For LineOfSight, the
+    // directions in which to look.
+    pub fn offsets(&self, dimensions: usize) -> Vec<Vec<isize>> {
+        match self {
+            Neighbourhood::Moore | Neighbourhood::LineOfSight => iter::repeat_n(-1..=1, dimensions)
+                .multi_cartesian_product()
+                .filter(|offset| offset.iter().any(|&delta| delta != 0))
+                .collect(),
+            Neighbourhood::VonNeumann => (0..dimensions)
+                .flat_map(|axis| {
+                    [-1, 1].into_iter().map(move |delta| {
+                        let mut offset = vec![0; dimensions];
+                        offset[axis] = delta;
+                        offset
+                    })
+                })
+                .collect(),
+            Neighbourhood::Hex => {
+                assert_eq!(dimensions, 2, "hex grids are two-dimensional");
+                Direction::ALL
+                    .iter()
+                    .map(|direction| {
+                        let unit = direction.unit();
+                        vec![unit.r as isize, unit.q as isize]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[test]
+fn test_offsets() {
+    assert_eq!(Neighbourhood::Moore.offsets(2).len(), 8);
+    assert_eq!(Neighbourhood::Moore.offsets(4).len(), 80);
+    assert_eq!(
+        Neighbourhood::VonNeumann.offsets(2),
+        [[-1, 0], [1, 0], [0, -1], [0, 1]]
+    );
+    assert_eq!(Neighbourhood::Hex.offsets(2).len(), 6);
+}
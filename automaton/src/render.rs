@@ -0,0 +1,424 @@
+// Pictures of 2D grids: as ASCII art, as PNG images, or as an animated GIF of
+// several generations. Cell values index into a palette, so grids with more
+// than live and dead cells, like heightmaps, can be drawn too.
+//
+// Cells are placed in units of half a cell across, so that hex grids can be
+// drawn with each row offset by half a cell from the row above. A hex grid is
+// in axial coordinates with rows along axis 0, as Neighbourhood::Hex expects
+// when axis 1 points east.
+
+use crate::engine::Automaton;
+use crate::sparse::SparseAutomaton;
+use ndarray::{Array2, Ix2};
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Square,
+    Hex,
+}
+
+impl Layout {
+    // How far across a cell is, in half cells
+    fn across(self, row: isize, column: isize) -> isize {
+        match self {
+            Layout::Square => 2 * column,
+            Layout::Hex => 2 * column + row,
+        }
+    }
+}
+
+// One generation of a 2D grid, with the coordinates of its first cell, so
+// that frames of a growing grid line up
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub origin: [isize; 2],
+    pub cells: Array2<u8>,
+}
+
+impl Frame {
+    pub fn new(cells: Array2<u8>) -> Self {
+        Frame {
+            origin: [0, 0],
+            cells,
+        }
+    }
+
+    // Panics if the automaton isn't two-dimensional
+    pub fn of(automaton: &Automaton) -> Self {
+        let cells = automaton.cells().clone().into_dimensionality::<Ix2>();
+        Frame {
+            origin: [automaton.origin()[0], automaton.origin()[1]],
+            cells: cells.expect("only 2D grids can be drawn"),
+        }
+    }
+
+    // The plane of the first two axes where the other coordinates are
+    // `rest`, big enough for the live cells in any such plane
+    pub fn of_sparse(automaton: &SparseAutomaton, rest: &[i32]) -> Self {
+        let (mut low, mut high) = ([isize::MAX; 2], [isize::MIN; 2]);
+        for cell in automaton.cells() {
+            for axis in 0..2 {
+                low[axis] = low[axis].min(cell[axis] as isize);
+                high[axis] = high[axis].max(cell[axis] as isize);
+            }
+        }
+        if low[0] > high[0] {
+            return Frame::new(Array2::zeros((0, 0)));
+        }
+        let shape = (
+            (high[0] - low[0] + 1) as usize,
+            (high[1] - low[1] + 1) as usize,
+        );
+        let mut coordinates = vec![0; 2 + rest.len()];
+        coordinates[2..].copy_from_slice(rest);
+        // With symmetric axes, only one cell of each family is stored, so
+        // look the cells up rather than picking out the stored ones
+        let cells = Array2::from_shape_fn(shape, |(i, j)| {
+            coordinates[0] = (low[0] + i as isize) as i32;
+            coordinates[1] = (low[1] + j as isize) as i32;
+            automaton.is_live(&coordinates) as u8
+        });
+        Frame { origin: low, cells }
+    }
+}
+
+// What each cell value looks like: a character for ASCII and a colour for
+// images. Values past the end use the last entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    entries: Vec<(char, [u8; 3])>,
+}
+
+impl Palette {
+    // Panics unless there are between 1 and 256 entries
+    pub fn new(entries: Vec<(char, [u8; 3])>) -> Self {
+        assert!(
+            (1..=256).contains(&entries.len()),
+            "palette needs 1 to 256 entries"
+        );
+        Palette { entries }
+    }
+
+    // Dead cells as dark '.' and live cells as light '#'
+    pub fn binary() -> Self {
+        Palette::new(vec![('.', [0x20, 0x20, 0x28]), ('#', [0xf0, 0xe0, 0x90])])
+    }
+
+    fn index(&self, value: u8) -> u8 {
+        value.min((self.entries.len() - 1) as u8)
+    }
+
+    fn rgb(&self) -> Vec<u8> {
+        self.entries.iter().flat_map(|(_, rgb)| *rgb).collect()
+    }
+}
+
+// For puzzles that can be drawn: the frames to draw for each part, and how to
+// draw them. A legend can explain what the frames show.
+pub trait Draw {
+    fn renderer(&self) -> Renderer;
+    fn frames(&self, is_part2: bool) -> Vec<Frame>;
+
+    fn legend(&self, _is_part2: bool) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderError {
+    TooLarge { width: usize, height: usize },
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::TooLarge { width, height } => {
+                write!(f, "Image of {}x{} pixels is too large", width, height)
+            }
+            RenderError::Png(err) => write!(f, "Error writing PNG: {}", err),
+            RenderError::Gif(err) => write!(f, "Error writing GIF: {}", err),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+impl From<png::EncodingError> for RenderError {
+    fn from(err: png::EncodingError) -> Self {
+        RenderError::Png(err)
+    }
+}
+
+impl From<gif::EncodingError> for RenderError {
+    fn from(err: gif::EncodingError) -> Self {
+        RenderError::Gif(err)
+    }
+}
+
+// The area covered by some frames, in half cells across and rows down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bounds {
+    left: isize,
+    top: isize,
+    right: isize,
+    bottom: isize,
+}
+
+// Where nothing is drawn, a single dead cell
+const EMPTY: Bounds = Bounds {
+    left: 0,
+    top: 0,
+    right: 0,
+    bottom: 0,
+};
+
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    layout: Layout,
+    palette: Palette,
+    // Pixels per cell in images
+    scale: usize,
+}
+
+impl Renderer {
+    pub fn new(layout: Layout) -> Self {
+        Renderer {
+            layout,
+            palette: Palette::binary(),
+            scale: 4,
+        }
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    // Hex rows are offset by half of this, so it had better be even for them
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        assert!(scale > 0);
+        self.scale = scale;
+        self
+    }
+
+    // The positions of a frame's cells, with their values
+    fn place<'a>(&self, frame: &'a Frame) -> impl Iterator<Item = (isize, isize, u8)> + 'a {
+        let layout = self.layout;
+        frame.cells.indexed_iter().map(move |((i, j), &value)| {
+            let row = frame.origin[0] + i as isize;
+            let column = frame.origin[1] + j as isize;
+            (row, layout.across(row, column), value)
+        })
+    }
+
+    fn bounds(&self, frames: &[Frame]) -> Option<Bounds> {
+        let corners = frames
+            .iter()
+            .filter(|frame| !frame.cells.is_empty())
+            .flat_map(|frame| {
+                let (rows, columns) = frame.cells.dim();
+                let [top, left] = frame.origin;
+                let (bottom, right) = (top + rows as isize - 1, left + columns as isize - 1);
+                [(top, left), (top, right), (bottom, left), (bottom, right)]
+            })
+            .map(|(row, column)| (row, self.layout.across(row, column)));
+        corners.fold(None, |bounds, (row, across)| {
+            let b = bounds.unwrap_or(Bounds {
+                left: across,
+                top: row,
+                right: across,
+                bottom: row,
+            });
+            Some(Bounds {
+                left: b.left.min(across),
+                top: b.top.min(row),
+                right: b.right.max(across),
+                bottom: b.bottom.max(row),
+            })
+        })
+    }
+
+    // One line per row. Hex cells are two characters wide, so that rows can
+    // be offset by one character.
+    pub fn ascii(&self, frame: &Frame) -> String {
+        let bounds = match self.bounds(std::slice::from_ref(frame)) {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let per_half = match self.layout {
+            Layout::Square => 1,
+            Layout::Hex => 2,
+        };
+        let width = ((bounds.right - bounds.left) * per_half / 2 + 1) as usize;
+        let height = (bounds.bottom - bounds.top + 1) as usize;
+        let mut lines = vec![vec![' '; width]; height];
+        for (row, across, value) in self.place(frame) {
+            let x = ((across - bounds.left) * per_half / 2) as usize;
+            let y = (row - bounds.top) as usize;
+            lines[y][x] = self.palette.entries[self.palette.index(value) as usize].0;
+        }
+        lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string() + "\n")
+            .collect()
+    }
+
+    // Palette indices for each pixel of the area, row by row
+    fn pixels(&self, frame: &Frame, bounds: Bounds) -> Vec<u8> {
+        let (width, height) = self.size(bounds);
+        let mut pixels = vec![0; width * height];
+        for (row, across, value) in self.place(frame) {
+            let x = (across - bounds.left) as usize * self.scale / 2;
+            let y = (row - bounds.top) as usize * self.scale;
+            let index = self.palette.index(value);
+            for line in pixels.chunks_mut(width).skip(y).take(self.scale) {
+                line[x..x + self.scale].fill(index);
+            }
+        }
+        pixels
+    }
+
+    // The image size in pixels. The encoders need it to fit in their own
+    // integer types, which checked_size() checks.
+    fn size(&self, bounds: Bounds) -> (usize, usize) {
+        let width = (bounds.right - bounds.left) as usize * self.scale / 2 + self.scale;
+        let height = (bounds.bottom - bounds.top + 1) as usize * self.scale;
+        (width, height)
+    }
+
+    fn checked_size<T: TryFrom<usize>>(&self, bounds: Bounds) -> Result<(T, T), RenderError> {
+        let (width, height) = self.size(bounds);
+        match (T::try_from(width), T::try_from(height)) {
+            (Ok(w), Ok(h)) => Ok((w, h)),
+            _ => Err(RenderError::TooLarge { width, height }),
+        }
+    }
+
+    pub fn png(&self, frame: &Frame, writer: impl Write) -> Result<(), RenderError> {
+        let bounds = self.bounds(std::slice::from_ref(frame)).unwrap_or(EMPTY);
+        let (width, height) = self.checked_size::<u32>(bounds)?;
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.palette.rgb());
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels(frame, bounds))?;
+        Ok(())
+    }
+
+    // An animation looping over the frames, each shown for `delay` hundredths
+    // of a second. All frames are drawn on an area big enough for any of
+    // them.
+    pub fn gif(&self, frames: &[Frame], delay: u16, writer: impl Write) -> Result<(), RenderError> {
+        let bounds = self.bounds(frames).unwrap_or(EMPTY);
+        let (width, height) = self.checked_size::<u16>(bounds)?;
+        let mut encoder = gif::Encoder::new(writer, width, height, &self.palette.rgb())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for frame in frames {
+            let mut image =
+                gif::Frame::from_indexed_pixels(width, height, self.pixels(frame, bounds), None);
+            image.delay = delay;
+            encoder.write_frame(&image)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use crate::neighbourhood::Neighbourhood;
+#[cfg(test)]
+use crate::rule::Rule;
+#[cfg(test)]
+use itertools::Itertools;
+#[cfg(test)]
+use ndarray::array;
+
+#[test]
+fn test_ascii() {
+    let frame = Frame::new(array![[0, 1, 0], [0, 0, 1], [1, 1, 1]]);
+    assert_eq!(
+        Renderer::new(Layout::Square).ascii(&frame),
+        ".#.\n..#\n###\n"
+    );
+    let hex = Renderer::new(Layout::Hex).ascii(&Frame::new(array![[1, 0], [1, 1], [0, 1]]));
+    assert_eq!(hex, "# .\n # #\n  . #\n");
+
+    let palette = Palette::new(vec![(' ', [0; 3]), ('1', [1; 3]), ('+', [2; 3])]);
+    let renderer = Renderer::new(Layout::Square).with_palette(palette);
+    assert_eq!(renderer.ascii(&Frame::new(array![[2, 1, 9, 0]])), "+1+\n");
+}
+
+#[test]
+fn test_sparse_plane() {
+    let live = [vec![0, 1, 0], vec![1, 2, 1], vec![2, 0, -1]];
+    let automaton = SparseAutomaton::new(3, live, &Rule::life());
+    let renderer = Renderer::new(Layout::Square);
+    assert_eq!(
+        renderer.ascii(&Frame::of_sparse(&automaton, &[0])),
+        ".#.\n...\n...\n"
+    );
+    assert_eq!(
+        renderer.ascii(&Frame::of_sparse(&automaton, &[1])),
+        "...\n..#\n...\n"
+    );
+}
+
+#[test]
+fn test_png() {
+    let frame = Frame::new(array![[0, 1], [1, 1]]);
+    let mut data = vec![];
+    let renderer = Renderer::new(Layout::Hex).with_scale(2);
+    renderer.png(&frame, &mut data).unwrap();
+
+    let decoder = png::Decoder::new(data.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!((info.width, info.height), (5, 4));
+    #[rustfmt::skip]
+    assert_eq!(pixels, [
+        0, 0, 1, 1, 0,
+        0, 0, 1, 1, 0,
+        0, 1, 1, 1, 1,
+        0, 1, 1, 1, 1,
+    ]);
+}
+
+// Frames of a growing grid are drawn in the same place
+#[test]
+fn test_gif() {
+    let start = array![[0, 1, 0], [0, 1, 0], [0, 1, 0]].into_dyn();
+    let mut automaton = Automaton::new(start, Neighbourhood::Moore, &Rule::life());
+    let mut frames = vec![Frame::of(&automaton)];
+    for _ in 0..2 {
+        automaton.step();
+        frames.push(Frame::of(&automaton));
+    }
+    let renderer = Renderer::new(Layout::Square).with_scale(1);
+    assert_eq!(
+        renderer.bounds(&frames).map(|b| renderer.size(b)),
+        Some((5, 5))
+    );
+    let mut data = vec![];
+    renderer.gif(&frames, 50, &mut data).unwrap();
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::Indexed);
+    let mut decoder = options.read_info(data.as_slice()).unwrap();
+    let mut images = vec![];
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!(frame.delay, 50);
+        images.push(frame.buffer.to_vec());
+    }
+    assert_eq!(images.len(), 3);
+    let live =
+        |image: &Vec<u8>| -> Vec<usize> { image.iter().positions(|&pixel| pixel == 1).collect() };
+    assert_eq!(live(&images[0]), [7, 12, 17]);
+    assert_eq!(live(&images[1]), [11, 12, 13]);
+    assert_eq!(live(&images[2]), [7, 12, 17]);
+}
@@ -0,0 +1,51 @@
+// Which cells are alive in the next generation, given whether they are alive
+// now and how many of their neighbours are. Conway's Life is B3/S23: birth
+// with exactly 3 neighbours, survival with 2 or 3.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: Vec<usize>,
+    pub survival: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new(
+        birth: impl IntoIterator<Item = usize>,
+        survival: impl IntoIterator<Item = usize>,
+    ) -> Self {
+        Rule {
+            birth: birth.into_iter().collect(),
+            survival: survival.into_iter().collect(),
+        }
+    }
+
+    pub fn life() -> Self {
+        Rule::new([3], [2, 3])
+    }
+
+    pub fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survival.contains(&neighbours)
+        } else {
+            self.birth.contains(&neighbours)
+        }
+    }
+
+    // The next state for every possible neighbour count up to `max`, indexed
+    // by [alive as usize][count]
+    pub(crate) fn table(&self, max: usize) -> [Vec<u8>; 2] {
+        let row = |alive| {
+            (0..=max)
+                .map(|count| self.next(alive, count) as u8)
+                .collect()
+        };
+        [row(false), row(true)]
+    }
+}
+
+#[test]
+fn test_rule() {
+    let life = Rule::life();
+    assert!(life.next(false, 3) && !life.next(false, 2));
+    assert!(life.next(true, 2) && !life.next(true, 4));
+    assert_eq!(life.table(4), [vec![0, 0, 0, 1, 0], vec![0, 0, 1, 1, 0]]);
+}
@@ -0,0 +1,226 @@
+// A Moore-neighbourhood automaton that stores only its live cells, for grids
+// with too many dimensions to store densely.
+//
+// A 2D seed in a higher-dimensional space stays symmetric in the extra axes:
+// mirroring any of them, or swapping any two, gives the same state. With
+// symmetry turned on, only one cell of each such family is stored: the one
+// whose extra coordinates are non-negative and sorted. Each stored cell stands
+// for all the cells of its family when counting the population.
+
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::Rule;
+use std::collections::HashSet;
+use std::hash::{BuildHasherDefault, Hasher};
+
+type Cell = Vec<i32>;
+type CellSet = HashSet<Cell, BuildHasherDefault<CellHasher>>;
+
+// The multiply-rotate hash from the Firefox/rustc FxHasher. Hashing cells is
+// most of the work here, and they don't need the default DoS resistance.
+#[derive(Default)]
+struct CellHasher(u64);
+
+impl Hasher for CellHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseAutomaton {
+    live: CellSet,
+    // Axes from here on are symmetric; equal to the number of dimensions if
+    // symmetry isn't used
+    first_symmetric: usize,
+    offsets: Vec<Cell>,
+    rule: Rule,
+    generation: usize,
+}
+
+impl SparseAutomaton {
+    pub fn new(dimensions: usize, live: impl IntoIterator<Item = Cell>, rule: &Rule) -> Self {
+        let offsets = Neighbourhood::Moore
+            .offsets(dimensions)
+            .into_iter()
+            .map(|offset| offset.into_iter().map(|delta| delta as i32).collect())
+            .collect();
+        let live: CellSet = live.into_iter().collect();
+        assert!(live.iter().all(|cell| cell.len() == dimensions));
+        SparseAutomaton {
+            live,
+            first_symmetric: dimensions,
+            offsets,
+            rule: rule.clone(),
+            generation: 0,
+        }
+    }
+
+    // Treats the axes from `first` on as symmetric. Panics if the live cells
+    // aren't, which they are if they all have 0 for those coordinates.
+    pub fn with_symmetric_axes(mut self, first: usize) -> Self {
+        let n_cells = self.live.len();
+        self.first_symmetric = first;
+        self.live = self.live.iter().map(|cell| self.canonical(cell)).collect();
+        assert_eq!(self.population(), n_cells, "live cells are not symmetric");
+        self
+    }
+
+    fn canonical(&self, cell: &[i32]) -> Cell {
+        let mut canonical = cell.to_vec();
+        self.make_canonical(&mut canonical);
+        canonical
+    }
+
+    fn make_canonical(&self, cell: &mut [i32]) {
+        let extra = &mut cell[self.first_symmetric..];
+        extra.iter_mut().for_each(|x| *x = x.abs());
+        extra.sort_unstable();
+    }
+
+    // How many cells a stored cell stands for: every ordering of its extra
+    // coordinates, with every combination of signs for the non-zero ones
+    fn family_size(&self, cell: &[i32]) -> usize {
+        let extra = &cell[self.first_symmetric..];
+        let mut orderings = factorial(extra.len());
+        for run in extra.chunk_by(|a, b| a == b) {
+            orderings /= factorial(run.len());
+        }
+        orderings << extra.iter().filter(|&&x| x != 0).count()
+    }
+
+    // Counts live neighbours reusing one buffer, since this is the hot loop
+    fn live_neighbours(&self, cell: &[i32], buffer: &mut Cell) -> usize {
+        self.offsets
+            .iter()
+            .filter(|offset| {
+                buffer.clear();
+                buffer.extend(cell.iter().zip(*offset).map(|(x, dx)| x + dx));
+                self.make_canonical(buffer);
+                self.live.contains(buffer.as_slice())
+            })
+            .count()
+    }
+
+    pub fn is_live(&self, cell: &[i32]) -> bool {
+        self.live.contains(&self.canonical(cell))
+    }
+
+    // The stored cells: with symmetry, one of each family
+    pub fn cells(&self) -> impl Iterator<Item = &[i32]> {
+        self.live.iter().map(|cell| cell.as_slice())
+    }
+
+    pub fn population(&self) -> usize {
+        self.live.iter().map(|cell| self.family_size(cell)).sum()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Only live cells and their neighbours can be alive in the next
+    // generation. Because the state is symmetric, a cell's neighbours can be
+    // looked up by their canonical form.
+    pub fn step(&mut self) {
+        let mut candidates = self.live.clone();
+        let mut buffer = vec![];
+        for cell in &self.live {
+            for offset in &self.offsets {
+                buffer.clear();
+                buffer.extend(cell.iter().zip(offset).map(|(x, dx)| x + dx));
+                self.make_canonical(&mut buffer);
+                if !candidates.contains(buffer.as_slice()) {
+                    candidates.insert(buffer.clone());
+                }
+            }
+        }
+        self.live = candidates
+            .into_iter()
+            .filter(|cell| {
+                let count = self.live_neighbours(cell, &mut buffer);
+                self.rule.next(self.live.contains(cell), count)
+            })
+            .collect();
+        self.generation += 1;
+    }
+}
+
+fn factorial(n: usize) -> usize {
+    (1..=n).product()
+}
+
+#[cfg(test)]
+fn glider(dimensions: usize) -> Vec<Cell> {
+    [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]]
+        .iter()
+        .map(|xy| {
+            let mut cell = xy.to_vec();
+            cell.resize(dimensions, 0);
+            cell
+        })
+        .collect()
+}
+
+#[test]
+fn test_family_size() {
+    let automaton = SparseAutomaton::new(4, [], &Rule::life()).with_symmetric_axes(2);
+    assert_eq!(automaton.family_size(&[5, 5, 0, 0]), 1);
+    assert_eq!(automaton.family_size(&[5, 5, 0, 1]), 4);
+    assert_eq!(automaton.family_size(&[5, 5, 1, 1]), 4);
+    assert_eq!(automaton.family_size(&[5, 5, 1, 2]), 8);
+    assert_eq!(automaton.canonical(&[5, -5, -2, 1]), [5, -5, 1, 2]);
+}
+
+// The same as the dense automaton, with and without symmetry
+#[test]
+fn test_against_dense() {
+    use crate::engine::Automaton;
+    use ndarray::ArrayD;
+
+    for dimensions in 2..=4 {
+        let mut shape = vec![3, 3];
+        shape.resize(dimensions, 1);
+        let mut cells = ArrayD::zeros(shape);
+        for cell in glider(dimensions) {
+            let index: Vec<_> = cell.iter().map(|&x| x as usize).collect();
+            cells[index.as_slice()] = 1;
+        }
+        let mut dense = Automaton::new(cells, Neighbourhood::Moore, &Rule::life());
+        let mut sparse = SparseAutomaton::new(dimensions, glider(dimensions), &Rule::life());
+        let mut symmetric = sparse.clone().with_symmetric_axes(2);
+        for _ in 0..4 {
+            dense.step();
+            sparse.step();
+            symmetric.step();
+            assert_eq!(sparse.population(), dense.population());
+            assert_eq!(symmetric.population(), dense.population());
+        }
+        assert!(symmetric.live.len() <= sparse.live.len());
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_asymmetric_seed() {
+    SparseAutomaton::new(3, [vec![0, 0, 1]], &Rule::life()).with_symmetric_axes(2);
+}
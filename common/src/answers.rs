@@ -0,0 +1,125 @@
+use crate::input::workspace_dir;
+use crate::solution::Answer;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml::value::{Table, Value};
+
+// Recorded answers, keyed by year, day, and part:
+//
+//     [2020.1]
+//     part1 = 776064
+//     part2 = 6964490
+pub struct Answers {
+    table: Table,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    File(PathBuf, io::Error),
+    Toml(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::File(path, err) => write!(f, "{}: {}", path.display(), err),
+            AnswersError::Toml(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+impl Error for AnswersError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnswersError::File(_, err) => Some(err),
+            AnswersError::Toml(_, err) => Some(err),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+pub fn answers_path() -> PathBuf {
+    workspace_dir().join("answers.toml")
+}
+
+impl FromStr for Answers {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Answers {
+            table: toml::from_str(s)?,
+        })
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        let text = fs::read_to_string(path).map_err(|err| AnswersError::File(path.into(), err))?;
+        text.parse()
+            .map_err(|err| AnswersError::Toml(path.into(), err))
+    }
+
+    pub fn expected(&self, year: u16, day: u8, is_part2: bool) -> Option<String> {
+        let part = if is_part2 { "part2" } else { "part1" };
+        let value = self
+            .table
+            .get(&year.to_string())?
+            .get(day.to_string())?
+            .get(part)?;
+        Some(match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    // Answers are compared by how they are displayed, so a number recorded as
+    // a string still matches. Notes on an answer don't count.
+    pub fn check(&self, year: u16, day: u8, is_part2: bool, answer: &Answer) -> Verdict {
+        match self.expected(year, day, is_part2) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer.value().to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected },
+        }
+    }
+}
+
+#[test]
+fn check_answers() {
+    let answers: Answers = "[2020.21]\npart1 = 1679\npart2 = \"lmxt,rggkbpj\"\n"
+        .parse()
+        .unwrap();
+    assert_eq!(answers.expected(2020, 21, false), Some("1679".into()));
+    assert_eq!(
+        answers.check(2020, 21, false, &Answer::from(1679)),
+        Verdict::Pass
+    );
+    assert_eq!(
+        answers.check(2020, 21, true, &Answer::from("lmxt")),
+        Verdict::Fail {
+            expected: "lmxt,rggkbpj".into()
+        }
+    );
+    assert_eq!(
+        answers.check(2020, 22, false, &Answer::from(1)),
+        Verdict::Missing
+    );
+    assert_eq!(
+        answers.check(2020, 21, false, &Answer::from(1679).noted("note")),
+        Verdict::Pass
+    );
+}
+
+#[test]
+fn recorded_answers_parse() {
+    Answers::load(&answers_path()).unwrap();
+}
@@ -0,0 +1,160 @@
+use crate::cli::Part;
+use crate::solution::{parse, Solution};
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Stage {
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Option<Self> {
+        samples.sort_unstable();
+        Some(Stats {
+            min: *samples.first()?,
+            median: samples[samples.len() / 2],
+            max: *samples.last()?,
+        })
+    }
+}
+
+// Times parsing and each requested part separately, once per iteration
+pub fn bench<S: Solution>(
+    input: &str,
+    part: Part,
+    iterations: usize,
+) -> Result<Vec<(Stage, Stats)>, Box<dyn Error>> {
+    let mut parse_samples = vec![];
+    let mut part_samples: Vec<_> = part.part2_flags().map(|flag| (flag, vec![])).collect();
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let puzzle = black_box(parse::<S>(black_box(input))?);
+        parse_samples.push(start.elapsed());
+        for (is_part2, samples) in &mut part_samples {
+            let start = Instant::now();
+            black_box(puzzle.solve(*is_part2));
+            samples.push(start.elapsed());
+        }
+    }
+
+    let mut results = vec![];
+    results.extend(Stats::from_samples(&mut parse_samples).map(|stats| (Stage::Parse, stats)));
+    for (is_part2, mut samples) in part_samples {
+        let stage = if is_part2 { Stage::Part2 } else { Stage::Part1 };
+        results.extend(Stats::from_samples(&mut samples).map(|stats| (stage, stats)));
+    }
+    Ok(results)
+}
+
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+// Slowest first
+pub fn sort_rows(rows: &mut [Row]) {
+    rows.sort_by_key(|row| Reverse(row.stats.median));
+}
+
+pub fn format_table(rows: &[Row]) -> String {
+    let mut table = format!(
+        "{:<4} {:>3} {:<5} {:>12} {:>12} {:>12}\n",
+        "year", "day", "stage", "min", "median", "max"
+    );
+    for row in rows {
+        writeln!(
+            table,
+            "{:<4} {:>3} {:<5} {:>12} {:>12} {:>12}",
+            row.year,
+            row.day,
+            row.stage.name(),
+            format!("{:.3?}", row.stats.min),
+            format!("{:.3?}", row.stats.median),
+            format!("{:.3?}", row.stats.max),
+        )
+        .unwrap();
+    }
+    table
+}
+
+pub fn format_json(rows: &[Row], iterations: usize) -> String {
+    let entries: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            format!(
+                "  {{\"year\": {}, \"day\": {}, \"stage\": \"{}\", \"iterations\": {}, \
+                 \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+                row.year,
+                row.day,
+                row.stage.name(),
+                iterations,
+                row.stats.min.as_nanos(),
+                row.stats.median.as_nanos(),
+                row.stats.max.as_nanos()
+            )
+        })
+        .collect();
+    format!("[\n{}\n]", entries.join(",\n"))
+}
+
+#[test]
+fn stats_from_samples() {
+    let mut samples: Vec<_> = [5, 1, 3, 2, 4]
+        .iter()
+        .map(|&ms| Duration::from_millis(ms))
+        .collect();
+    assert_eq!(
+        Stats::from_samples(&mut samples),
+        Some(Stats {
+            min: Duration::from_millis(1),
+            median: Duration::from_millis(3),
+            max: Duration::from_millis(5),
+        })
+    );
+    assert_eq!(Stats::from_samples(&mut []), None);
+}
+
+#[test]
+fn json_rows() {
+    let stats = Stats {
+        min: Duration::from_nanos(1),
+        median: Duration::from_nanos(2),
+        max: Duration::from_nanos(3),
+    };
+    let rows = [Row {
+        year: 2020,
+        day: 15,
+        stage: Stage::Part2,
+        stats,
+    }];
+    assert_eq!(
+        format_json(&rows, 4),
+        "[\n  {\"year\": 2020, \"day\": 15, \"stage\": \"part2\", \"iterations\": 4, \
+         \"min_ns\": 1, \"median_ns\": 2, \"max_ns\": 3}\n]"
+    );
+}
@@ -0,0 +1,179 @@
+use crate::input::Source;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const OPTIONS_HELP: &str = "Options:
+  --part 1|2|both   Which part to solve (default: both)
+  --input <path>    Read the input from <path> instead, or from stdin if '-'
+  --example         Read the example input (test_input) instead
+  --iterations <n>  How many times bench runs each day (default: 10)
+  --json            Print bench results as JSON
+  --help            Show this message";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    // The values of is_part2 to run the puzzle with, in order
+    pub fn part2_flags(self) -> impl Iterator<Item = bool> {
+        let flags: &[bool] = match self {
+            Part::One => &[false],
+            Part::Two => &[true],
+            Part::Both => &[false, true],
+        };
+        flags.iter().copied()
+    }
+}
+
+impl FromStr for Part {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            _ => Err(CliError::BadPart(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    MissingValue(&'static str),
+    BadPart(String),
+    BadIterations(String),
+    UnknownFlag(String),
+    ConflictingInputs,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::MissingValue(flag) => write!(f, "{} requires a value", flag),
+            CliError::BadPart(part) => {
+                write!(f, "Bad part {:?}, expected 1, 2, or both", part)
+            }
+            CliError::BadIterations(n) => {
+                write!(f, "Bad iteration count {:?}, expected a positive number", n)
+            }
+            CliError::UnknownFlag(flag) => write!(f, "Unknown option {}", flag),
+            CliError::ConflictingInputs => write!(f, "--input and --example both given"),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub part: Part,
+    pub input: Option<PathBuf>,
+    pub example: bool,
+    pub iterations: usize,
+    pub json: bool,
+    pub help: bool,
+    pub args: Vec<String>,
+}
+
+impl Options {
+    // Parses the arguments after the program name. Anything that isn't a flag
+    // is collected into args, for the runner to interpret.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Options {
+            part: Part::Both,
+            input: None,
+            example: false,
+            iterations: 10,
+            json: false,
+            help: false,
+            args: vec![],
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    let part = args.next().ok_or(CliError::MissingValue("--part"))?;
+                    options.part = part.parse()?;
+                }
+                "--input" => {
+                    let path = args.next().ok_or(CliError::MissingValue("--input"))?;
+                    options.input = Some(path.into());
+                }
+                "--example" => options.example = true,
+                "--iterations" => {
+                    let n = args.next().ok_or(CliError::MissingValue("--iterations"))?;
+                    options.iterations = n
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or(CliError::BadIterations(n))?;
+                }
+                "--json" => options.json = true,
+                "-h" | "--help" => options.help = true,
+                flag if flag.starts_with('-') && flag != "-" => {
+                    return Err(CliError::UnknownFlag(arg));
+                }
+                _ => options.args.push(arg),
+            }
+        }
+        if options.example && options.input.is_some() {
+            return Err(CliError::ConflictingInputs);
+        }
+        Ok(options)
+    }
+
+    pub fn source(&self, year: u16, day: u8) -> Source {
+        match &self.input {
+            Some(path) if path.as_os_str() == "-" => Source::Stdin,
+            Some(path) => Source::Path(path.clone()),
+            None if self.example => Source::Example { year, day },
+            None => Source::Puzzle { year, day },
+        }
+    }
+}
+
+#[cfg(test)]
+fn parse(args: &[&str]) -> Result<Options, CliError> {
+    Options::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn parse_flags() {
+    let options = parse(&["2020", "--part", "2", "17", "--input", "-"]).unwrap();
+    assert_eq!(options.part, Part::Two);
+    assert_eq!(options.args, ["2020", "17"]);
+    assert!(matches!(options.source(2020, 17), Source::Stdin));
+
+    let options = parse(&["--example", "20"]).unwrap();
+    assert_eq!(options.part, Part::Both);
+    assert!(matches!(
+        options.source(2020, 20),
+        Source::Example {
+            year: 2020,
+            day: 20
+        }
+    ));
+    assert_eq!(Part::Both.part2_flags().collect::<Vec<_>>(), [false, true]);
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(parse(&["--part", "3"]), Err(CliError::BadPart("3".into())));
+    assert_eq!(parse(&["--part"]), Err(CliError::MissingValue("--part")));
+    assert_eq!(parse(&["-x"]), Err(CliError::UnknownFlag("-x".into())));
+    assert_eq!(
+        parse(&["--iterations", "0"]),
+        Err(CliError::BadIterations("0".into()))
+    );
+    assert_eq!(
+        parse(&["--example", "--input", "foo"]),
+        Err(CliError::ConflictingInputs)
+    );
+}
@@ -0,0 +1,174 @@
+use std::error::Error;
+use std::fmt;
+
+// An error in a puzzle's input. Line and column count from 1, and the kind is
+// an error enum specific to each day.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError<K> {
+    pub line: usize,
+    pub column: usize,
+    pub kind: K,
+}
+
+impl<K> ParseError<K> {
+    pub fn new(line: usize, column: usize, kind: K) -> Self {
+        ParseError { line, column, kind }
+    }
+
+    // For parsers that only see one line, which is line 1 as far as they know
+    pub fn at_column(column: usize, kind: K) -> Self {
+        ParseError::new(1, column, kind)
+    }
+
+    // For parsers that see a piece of the input, starting after `lines` lines
+    pub fn offset_by(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    // For parsers that see part of a line, starting after `columns` columns
+    pub fn offset_column(mut self, columns: usize) -> Self {
+        self.column += columns;
+        self
+    }
+}
+
+impl<K: fmt::Display> ParseError<K> {
+    // Renders the error together with the line of input it points to
+    pub fn diagnostic(&self, input: &str) -> String {
+        let mut message = format!("error: {}", self);
+        let line = self.line.max(1);
+        if let Some(text) = input.lines().nth(line - 1) {
+            let number = line.to_string();
+            let gutter = " ".repeat(number.len());
+            let column = self.column.clamp(1, text.chars().count() + 1);
+            message += &format!(
+                "\n{0} |\n{1} | {2}\n{0} | {3}^",
+                gutter,
+                number,
+                text,
+                " ".repeat(column - 1)
+            );
+        }
+        message
+    }
+}
+
+impl<K: fmt::Display> fmt::Display for ParseError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl<K: fmt::Display + fmt::Debug> Error for ParseError<K> {}
+
+// A ParseError rendered against its input, ready to print as is
+#[derive(Debug)]
+pub struct Diagnostic(pub String);
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for Diagnostic {}
+
+// Parses each line with `parse_line`, which reports errors as if its line were
+// line 1, and fixes up the line numbers
+pub fn parse_lines<T, K>(
+    input: &str,
+    mut parse_line: impl FnMut(&str) -> Result<T, ParseError<K>>,
+) -> Result<Vec<T>, ParseError<K>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(ix, line)| parse_line(line).map_err(|err| err.offset_by(ix)))
+        .collect()
+}
+
+// The 1-based column at which `part`, a slice of `line`, starts. A `part`
+// that isn't a slice of `line` gets column 1, so that a mistake in reporting
+// an error doesn't become a crash.
+pub fn column_of(line: &str, part: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (part.as_ptr() as usize).wrapping_sub(start);
+    if offset <= line.len() && part.len() <= line.len() - offset {
+        offset + 1
+    } else {
+        1
+    }
+}
+
+// How many lines of `input` come before `part`, a slice of it. Useful with
+// ParseError::offset_by for parsers that work on input::blocks().
+pub fn lines_before(input: &str, part: &str) -> usize {
+    input[..column_of(input, part) - 1].matches('\n').count()
+}
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+struct Bad(char);
+
+#[cfg(test)]
+impl fmt::Display for Bad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bad character '{}'", self.0)
+    }
+}
+
+#[test]
+fn line_numbers() {
+    let result = parse_lines("ab\ncd\nxf\n", |line| match line.find('x') {
+        Some(ix) => Err(ParseError::at_column(ix + 1, Bad('x'))),
+        None => Ok(line.len()),
+    });
+    assert_eq!(result, Err(ParseError::new(3, 1, Bad('x'))));
+    assert_eq!(
+        parse_lines("ab\ncd", |line| Ok::<_, ParseError<Bad>>(line.len())),
+        Ok(vec![2, 2])
+    );
+}
+
+#[test]
+fn diagnostic() {
+    let input = "fine\nalso fine\nnot ok\n";
+    let err = ParseError::new(3, 5, Bad('o'));
+    assert_eq!(
+        err.diagnostic(input),
+        "error: line 3, column 5: Bad character 'o'\n  |\n3 | not ok\n  |     ^"
+    );
+}
+
+#[test]
+fn columns() {
+    let line = "mem[8] = 11";
+    assert_eq!(column_of(line, &line[9..]), 10);
+    let input = "a\nb\n\nc\n";
+    let block = crate::input::blocks(input).nth(1).unwrap();
+    assert_eq!(lines_before(input, block), 3);
+    assert_eq!(column_of(line, &line[line.len()..]), 12);
+    assert_eq!(column_of(&line[4..], line), 1);
+}
+
+#[test]
+fn diagnostic_out_of_range() {
+    let input = "abc\n";
+    let caret = |line, column| {
+        let message = ParseError::new(line, column, Bad('x')).diagnostic(input);
+        message.lines().last().unwrap().to_string()
+    };
+    assert_eq!(caret(1, 0), "  | ^");
+    assert_eq!(caret(1, 100), "  |    ^");
+    assert!(ParseError::new(0, 1, Bad('x'))
+        .diagnostic(input)
+        .contains("1 | abc"));
+    assert_eq!(
+        ParseError::new(5, 1, Bad('x')).diagnostic(input),
+        "error: line 5, column 1: Bad character 'x'"
+    );
+}
@@ -0,0 +1,137 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum Source {
+    Puzzle { year: u16, day: u8 },
+    Example { year: u16, day: u8 },
+    Path(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    UnknownPuzzle { year: u16, day: u8 },
+    File(PathBuf, io::Error),
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::UnknownPuzzle { year, day } => {
+                write!(f, "No input known for {} day {}", year, day)
+            }
+            InputError::File(path, err) => write!(f, "{}: {}", path.display(), err),
+            InputError::Stdin(err) => write!(f, "<stdin>: {}", err),
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::UnknownPuzzle { .. } => None,
+            InputError::File(_, err) | InputError::Stdin(err) => Some(err),
+        }
+    }
+}
+
+pub(crate) fn workspace_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+pub fn puzzle_dir(year: u16, day: u8) -> Result<PathBuf, InputError> {
+    let dir = match (year, day) {
+        (2020, 1) => workspace_dir().join("puzzle1-1"),
+        (2020, 2..=25) => workspace_dir().join(format!("puzzle{}", day)),
+        (2021, 1..=25) => workspace_dir().join("advent2021/src/input"),
+        _ => return Err(InputError::UnknownPuzzle { year, day }),
+    };
+    Ok(dir)
+}
+
+pub fn puzzle_path(year: u16, day: u8) -> Result<PathBuf, InputError> {
+    let dir = puzzle_dir(year, day)?;
+    Ok(match year {
+        2021 => dir.join(format!("puzzle{}", day)),
+        _ => dir.join("input"),
+    })
+}
+
+pub fn example_path(year: u16, day: u8) -> Result<PathBuf, InputError> {
+    let dir = puzzle_dir(year, day)?;
+    Ok(match year {
+        2021 => dir.join(format!("puzzle{}_example", day)),
+        _ => dir.join("test_input"),
+    })
+}
+
+pub fn read(source: &Source) -> Result<String, InputError> {
+    let path = match source {
+        Source::Puzzle { year, day } => puzzle_path(*year, *day)?,
+        Source::Example { year, day } => example_path(*year, *day)?,
+        Source::Path(path) => path.clone(),
+        Source::Stdin => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(InputError::Stdin)?;
+            return Ok(text);
+        }
+    };
+    fs::read_to_string(&path).map_err(|err| InputError::File(path, err))
+}
+
+// Blocks are groups of lines separated by one or more blank lines
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split("\n\n")
+        .map(|block| block.trim_matches('\n'))
+        .filter(|block| !block.is_empty())
+}
+
+#[test]
+fn split_blocks() {
+    let input = "a\nb\n\nc\n\n\n\nd\ne\n";
+    assert_eq!(blocks(input).collect::<Vec<_>>(), ["a\nb", "c", "d\ne"]);
+    assert_eq!(blocks("").count(), 0);
+    assert_eq!(blocks("\n\n").count(), 0);
+}
+
+#[test]
+fn resolve_puzzle_paths() {
+    let root = workspace_dir();
+    assert_eq!(puzzle_path(2020, 1).unwrap(), root.join("puzzle1-1/input"));
+    assert_eq!(puzzle_path(2020, 17).unwrap(), root.join("puzzle17/input"));
+    assert_eq!(
+        puzzle_path(2021, 4).unwrap(),
+        root.join("advent2021/src/input/puzzle4")
+    );
+    assert_eq!(
+        example_path(2020, 20).unwrap(),
+        root.join("puzzle20/test_input")
+    );
+    assert!(matches!(
+        puzzle_path(2020, 26),
+        Err(InputError::UnknownPuzzle {
+            year: 2020,
+            day: 26
+        })
+    ));
+}
+
+#[test]
+fn read_missing_file() {
+    let path = workspace_dir().join("no such file");
+    match read(&Source::Path(path.clone())) {
+        Err(InputError::File(err_path, err)) => {
+            assert_eq!(err_path, path);
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,22 @@
+//! What every day's puzzle shares, for the `advent` runner to use.
+//!
+//! Each day implements the `solution::Solution` trait: `parse` reads the
+//! input once, and `part1` and `part2` return an `Answer` for the runner to
+//! print. `Solver::of` erases the day's type so that a runner can pick a day
+//! at runtime.
+//!
+//! When the input is malformed, `parse` returns an `error::ParseError` holding
+//! the line, the column, and a day-specific error enum. `ParseError::diagnostic`
+//! renders it with the offending line quoted.
+//!
+//! `input` finds each day's `input` and `test_input` files from the year and
+//! day, so the runner can be started from any directory. `answers` checks
+//! results against the answers recorded in `answers.toml`, `bench` times each
+//! stage of a day, and `cli` parses the options the runners share.
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod error;
+pub mod input;
+pub mod solution;
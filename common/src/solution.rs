@@ -0,0 +1,140 @@
+use crate::bench::{bench, Stage, Stats};
+use crate::cli::Part;
+use crate::error::{Diagnostic, ParseError};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    // No answer, either because the part doesn't have one or none was found
+    None,
+    // An answer with something about how it was found, printed after it
+    Noted(Box<Answer>, String),
+}
+
+impl Answer {
+    pub fn noted(self, note: impl Into<String>) -> Self {
+        Answer::Noted(Box::new(self), note.into())
+    }
+
+    // The answer without any notes, to compare with recorded answers
+    pub fn value(&self) -> &Answer {
+        match self {
+            Answer::Noted(answer, _) => answer.value(),
+            answer => answer,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "(none)"),
+            Answer::Noted(answer, note) => write!(f, "{} ({})", answer, note),
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+number_answer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(option: Option<T>) -> Self {
+        option.map_or(Answer::None, Into::into)
+    }
+}
+
+pub trait Solution: Sized {
+    // What can be wrong with the input, specific to the day
+    type Error: fmt::Display + fmt::Debug;
+
+    fn parse(input: &str) -> Result<Self, ParseError<Self::Error>>;
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+
+    fn solve(&self, is_part2: bool) -> Answer {
+        if is_part2 {
+            self.part2()
+        } else {
+            self.part1()
+        }
+    }
+}
+
+// Type-erased entry points that a runner can dispatch to for each day. Both
+// return results in the order given by Part::part2_flags().
+pub type SolveFn = fn(&str, Part) -> Result<Vec<Answer>, Box<dyn Error>>;
+pub type BenchFn = fn(&str, Part, usize) -> Result<Vec<(Stage, Stats)>, Box<dyn Error>>;
+
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub solve: SolveFn,
+    pub bench: BenchFn,
+}
+
+impl Solver {
+    pub fn of<S: Solution>() -> Self {
+        Solver {
+            solve: solve::<S>,
+            bench: bench::<S>,
+        }
+    }
+}
+
+pub fn solve<S: Solution>(input: &str, part: Part) -> Result<Vec<Answer>, Box<dyn Error>> {
+    let puzzle = parse::<S>(input)?;
+    Ok(part
+        .part2_flags()
+        .map(|is_part2| puzzle.solve(is_part2))
+        .collect())
+}
+
+// Parses the input, turning any error into a diagnostic that quotes the input
+pub fn parse<S: Solution>(input: &str) -> Result<S, Diagnostic> {
+    S::parse(input).map_err(|err| Diagnostic(err.diagnostic(input)))
+}
+
+pub fn print_answers(part: Part, answers: &[Answer]) {
+    for (is_part2, answer) in part.part2_flags().zip(answers) {
+        println!("Part {}: {}", is_part2 as u8 + 1, answer);
+    }
+}
+
+#[test]
+fn display_answers() {
+    assert_eq!(Answer::from(42u64).to_string(), "42");
+    assert_eq!(Answer::from(-7i32).to_string(), "-7");
+    assert_eq!(Answer::from("abc,def").to_string(), "abc,def");
+    assert_eq!(Answer::from(None::<u8>), Answer::None);
+    assert_eq!(Answer::from(Some(3usize)), Answer::Number(3));
+    let noted = Answer::from(8).noted("flipped instruction 7");
+    assert_eq!(noted.to_string(), "8 (flipped instruction 7)");
+    assert_eq!(noted.value(), &Answer::Number(8));
+}
@@ -0,0 +1,129 @@
+// Control-flow analysis of programs in the puzzle's instruction set. Each
+// instruction has exactly one successor, so the program is a graph in which
+// every node has one outgoing edge, plus one extra node for the end.
+
+use crate::program::{Instruction, Program};
+use crate::vm::{Status, VM};
+use bitvec::prelude::*;
+
+// The one instruction whose NOP/JMP flip makes the program end normally
+#[derive(Debug, PartialEq, Eq)]
+pub struct Repair {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub acc: i32,
+}
+
+// Where execution goes from each instruction, if it doesn't jump below 0 or
+// past the end
+fn successor(program: &Program, pc: usize) -> Option<usize> {
+    program[pc]
+        .next_pc(pc)
+        .filter(|&next| next <= program.len())
+}
+
+// For each address from 0 up to and including program.len(), whether running
+// from there ends the program normally. Computed by walking the edges
+// backwards from the end.
+pub fn terminating(program: &Program) -> BitVec {
+    let len = program.len();
+    let mut predecessors = vec![vec![]; len + 1];
+    for pc in 0..len {
+        if let Some(next) = successor(program, pc) {
+            predecessors[next].push(pc);
+        }
+    }
+    let mut reaches_end = bitvec![0; len + 1];
+    reaches_end.set(len, true);
+    let mut stack = vec![len];
+    while let Some(pc) = stack.pop() {
+        for &prev in &predecessors[pc] {
+            if !reaches_end[prev] {
+                reaches_end.set(prev, true);
+                stack.push(prev);
+            }
+        }
+    }
+    reaches_end
+}
+
+// Finds the repair in linear time. Only instructions that execute can make a
+// difference, so it follows the program from the start and checks for each
+// NOP or JMP whether the flipped instruction would lead into the terminating
+// set. Returns None if the program already ends, or if no flip fixes it.
+pub fn find_repair(program: &Program) -> Option<Repair> {
+    let reaches_end = terminating(program);
+    if reaches_end[0] {
+        return None;
+    }
+    let mut vm = VM::new(program.clone());
+    loop {
+        let pc = vm.pc();
+        if let Some(flipped) = program.get(pc)?.flipped() {
+            let next = flipped.next_pc(pc).filter(|&next| next <= program.len());
+            if next.is_some_and(|next| reaches_end[next]) {
+                let mut repaired = VM::new(program.clone());
+                repaired.program_mut()[pc] = flipped;
+                repaired.run().ok()?;
+                return Some(Repair {
+                    pc,
+                    instruction: flipped,
+                    acc: repaired.acc(),
+                });
+            }
+        }
+        if vm.step().ok()? == Status::Finished {
+            return None;
+        }
+    }
+}
+
+#[test]
+fn test_terminating() {
+    let program = Program::assemble(
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6",
+    )
+    .unwrap();
+    let reaches_end = terminating(&program);
+    assert_eq!(reaches_end, bits![0, 0, 0, 0, 0, 0, 0, 0, 1, 1]);
+    assert_eq!(
+        find_repair(&program),
+        Some(Repair {
+            pc: 7,
+            instruction: Instruction::NOP(-4),
+            acc: 8
+        })
+    );
+    assert_eq!(find_repair(&Program::assemble("acc +1").unwrap()), None);
+    // Flipping the nop would still end, but nothing needs repairing
+    let program = Program::assemble("nop +1\nacc +1").unwrap();
+    assert_eq!(find_repair(&program), None);
+}
+
+// The analysis agrees with trying every flip
+#[test]
+fn test_find_repair_matches_brute_force() {
+    let mut repaired = 0;
+    for seed in 1..=500 {
+        let program = crate::program::random_program(seed);
+        let fixes: Vec<_> = (0..program.len())
+            .filter_map(|pc| {
+                let mut vm = VM::new(program.clone());
+                vm.program_mut()[pc] = program[pc].flipped()?;
+                vm.run().ok()?;
+                Some((pc, vm.acc()))
+            })
+            .collect();
+        if VM::new(program.clone()).run().is_ok() {
+            continue;
+        }
+        match find_repair(&program) {
+            Some(repair) => {
+                assert!(fixes.contains(&(repair.pc, repair.acc)));
+                repaired += 1;
+            }
+            None => assert!(fixes.is_empty(), "missed {:?} in {}", fixes, program),
+        }
+    }
+    assert!(repaired > 0);
+}
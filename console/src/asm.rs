@@ -0,0 +1,204 @@
+// Text form of programs. Besides the plain puzzle input, the assembler accepts
+// `#` comments, blank lines, any amount of whitespace, and labels, which are
+// defined with `name:` before an instruction or on a line of their own, and
+// can be used instead of a number by any instruction taking a jump offset,
+// like nop and jmp:
+//
+//     loop:  acc +1   # count
+//            jmp loop
+//
+// The disassembler is the Display impl of Program, which always writes the
+// canonical form, one instruction per line with signed decimal offsets.
+//
+// Operands are separated by whitespace. Which mnemonics exist and what
+// operands they take is up to the instruction set's Op::parse.
+
+use crate::op::{is_label, AsmError, Op, Operand, Operands};
+use crate::program::{Instruction, Program};
+use crate::vm::VMError;
+use common::error::{column_of, ParseError};
+use std::collections::HashMap;
+use std::fmt;
+
+// An instruction whose operands may still be labels
+struct Unresolved<'a> {
+    line: usize,
+    text: &'a str,
+    mnemonic: &'a str,
+    operands: Vec<&'a str>,
+    // Where a missing operand would have been
+    end: &'a str,
+}
+
+impl Unresolved<'_> {
+    fn error(&self, part: &str, kind: VMError) -> ParseError<VMError> {
+        ParseError::new(self.line, column_of(self.text, part), kind)
+    }
+
+    fn assemble<I: Op>(
+        &self,
+        pc: usize,
+        labels: &HashMap<&str, usize>,
+    ) -> Result<I, ParseError<VMError>> {
+        let values: Vec<_> = self
+            .operands
+            .iter()
+            .map(|token| match (token.parse(), labels.get(token)) {
+                (Ok(n), _) => Operand::Number(n),
+                (_, Some(&target)) => Operand::Label(target as i64 - pc as i64),
+                _ => Operand::Name,
+            })
+            .collect();
+        I::parse(self.mnemonic, &Operands::new(&self.operands, &values)).map_err(|err| match err {
+            AsmError::UnknownOpcode => {
+                self.error(self.mnemonic, VMError::InvalidOpcode(self.mnemonic.into()))
+            }
+            AsmError::Operand(ix, kind) => {
+                self.error(self.operands.get(ix).unwrap_or(&self.end), kind)
+            }
+        })
+    }
+}
+
+pub fn assemble<I: Op>(source: &str) -> Result<Program<I>, ParseError<VMError>> {
+    let mut labels = HashMap::new();
+    let mut unresolved = vec![];
+    for (ix, text) in source.lines().enumerate() {
+        let code = text.split('#').next().unwrap_or_default();
+        let mut tokens = code.split_whitespace().peekable();
+        if let Some(label) = tokens.peek().and_then(|token| token.strip_suffix(':')) {
+            let error = |kind| ParseError::new(ix + 1, column_of(text, label), kind);
+            if !is_label(label) {
+                return Err(error(VMError::InvalidLabel(label.into())));
+            }
+            if labels.insert(label, unresolved.len()).is_some() {
+                return Err(error(VMError::DuplicateLabel(label.into())));
+            }
+            tokens.next();
+        }
+        let mnemonic = match tokens.next() {
+            Some(mnemonic) => mnemonic,
+            None => continue,
+        };
+        unresolved.push(Unresolved {
+            line: ix + 1,
+            text,
+            mnemonic,
+            operands: tokens.collect(),
+            end: &code[code.len()..],
+        });
+    }
+
+    unresolved
+        .iter()
+        .enumerate()
+        .map(|(pc, instruction)| instruction.assemble(pc, &labels))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Program::from)
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::NOP(param) => write!(f, "nop {:+}", param),
+            Instruction::ACC(param) => write!(f, "acc {:+}", param),
+            Instruction::JMP(param) => write!(f, "jmp {:+}", param),
+        }
+    }
+}
+
+impl<I: Op> fmt::Display for Program<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.instructions()
+            .iter()
+            .try_for_each(|instruction| writeln!(f, "{}", instruction))
+    }
+}
+
+#[test]
+fn test_labels_and_comments() {
+    let source = "# count to three
+start:
+    acc +1      # one
+    nop end
+  loop: acc   +2
+jmp loop
+end:";
+    assert_eq!(
+        assemble::<Instruction>(source).unwrap().to_string(),
+        "acc +1\nnop +3\nacc +2\njmp -1\n"
+    );
+}
+
+#[test]
+fn test_assemble_label_errors() {
+    assert_eq!(
+        assemble::<Instruction>("a: nop +0\n  a: jmp a").err(),
+        Some(ParseError::new(2, 3, VMError::DuplicateLabel("a".into())))
+    );
+    assert_eq!(
+        assemble::<Instruction>("jmp nowhere").err(),
+        Some(ParseError::new(
+            1,
+            5,
+            VMError::UndefinedLabel("nowhere".into())
+        ))
+    );
+    assert_eq!(
+        assemble::<Instruction>("x: acc x").err(),
+        Some(ParseError::new(1, 8, VMError::InvalidParameter("x".into())))
+    );
+    assert_eq!(
+        assemble::<Instruction>("1x: nop +0").err(),
+        Some(ParseError::new(1, 1, VMError::InvalidLabel("1x".into())))
+    );
+    assert_eq!(
+        assemble::<Instruction>("nop +0\n  jmp +1 +2").err(),
+        Some(ParseError::new(
+            2,
+            10,
+            VMError::UnexpectedToken("+2".into())
+        ))
+    );
+}
+
+// Every program survives disassembling and assembling again, and so does the
+// same program written with labels for its in-range jumps
+#[test]
+fn test_round_trip() {
+    for seed in 1..=200 {
+        let program = crate::program::random_program(seed);
+        let text = program.to_string();
+        assert_eq!(
+            assemble::<Instruction>(&text).as_ref(),
+            Ok(&program),
+            "{}",
+            text
+        );
+
+        let code = program.instructions();
+        let mut labels = vec![String::new(); code.len() + 1];
+        let mut lines: Vec<_> = code.iter().map(|i| format!("{} # was {}", i, i)).collect();
+        lines.push(String::new());
+        for (pc, instruction) in code.iter().enumerate() {
+            if let Some(target) = instruction.next_pc(pc).filter(|&t| t <= code.len()) {
+                if let Instruction::JMP(_) = instruction {
+                    lines[pc] = format!("jmp target{}", target);
+                    labels[target] = format!("target{}:", target);
+                }
+            }
+        }
+        let lines: Vec<_> = labels
+            .iter()
+            .zip(&lines)
+            .map(|(label, line)| format!("{:10} {}", label, line))
+            .collect();
+        let text = lines.join("\n");
+        assert_eq!(
+            assemble::<Instruction>(&text).as_ref(),
+            Ok(&program),
+            "{}",
+            text
+        );
+    }
+}
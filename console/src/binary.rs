@@ -0,0 +1,66 @@
+// A compact binary encoding of programs: three bytes per instruction, the
+// opcode followed by the parameter as a little-endian i16. Only for the
+// puzzle's own instruction set.
+
+use crate::program::{Instruction, Program};
+use crate::vm::VMError;
+
+const NOP: u8 = 0;
+const ACC: u8 = 1;
+const JMP: u8 = 2;
+
+pub fn encode(program: &Program) -> Vec<u8> {
+    program
+        .instructions()
+        .iter()
+        .flat_map(|instruction| {
+            let (opcode, param) = match *instruction {
+                Instruction::NOP(param) => (NOP, param),
+                Instruction::ACC(param) => (ACC, param),
+                Instruction::JMP(param) => (JMP, param),
+            };
+            let [lo, hi] = param.to_le_bytes();
+            [opcode, lo, hi]
+        })
+        .collect()
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Program, VMError> {
+    let chunks = bytes.chunks_exact(3);
+    if !chunks.remainder().is_empty() {
+        return Err(VMError::TruncatedBinary);
+    }
+    chunks
+        .map(|chunk| {
+            let param = i16::from_le_bytes([chunk[1], chunk[2]]);
+            match chunk[0] {
+                NOP => Ok(Instruction::NOP(param)),
+                ACC => Ok(Instruction::ACC(param)),
+                JMP => Ok(Instruction::JMP(param)),
+                opcode => Err(VMError::InvalidOpcode(format!("{:#04x}", opcode))),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Program::from)
+}
+
+#[test]
+fn test_encoding() {
+    let program = Program::assemble("nop +0\nacc -2\njmp +258").unwrap();
+    assert_eq!(encode(&program), [0, 0, 0, 1, 0xfe, 0xff, 2, 2, 1]);
+    assert_eq!(decode(&[0, 0]), Err(VMError::TruncatedBinary));
+    assert_eq!(
+        decode(&[3, 0, 0]),
+        Err(VMError::InvalidOpcode("0x03".into()))
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    for seed in 1..=200 {
+        let program = crate::program::random_program(seed);
+        let bytes = encode(&program);
+        assert_eq!(bytes.len(), program.len() * 3);
+        assert_eq!(decode(&bytes), Ok(program));
+    }
+}
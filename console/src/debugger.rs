@@ -0,0 +1,300 @@
+use crate::op::Op;
+use crate::program::Instruction;
+use crate::vm::{Status, VMError, VM};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint<I = Instruction> {
+    // Stop before executing the instruction at this address
+    Pc(usize),
+    // Stop before executing any instruction equal to this one
+    Instruction(I),
+}
+
+impl<I: Op> Breakpoint<I> {
+    fn hit(&self, vm: &VM<I>) -> bool {
+        match *self {
+            Breakpoint::Pc(pc) => vm.pc() == pc,
+            Breakpoint::Instruction(instruction) => vm.program().get(vm.pc()) == Some(instruction),
+        }
+    }
+}
+
+// Why Debugger::resume() gave control back
+#[derive(Debug, PartialEq)]
+pub enum Stop<I = Instruction> {
+    Breakpoint(Breakpoint<I>),
+    AccChanged { old: i32, new: i32 },
+    Finished,
+    Halted(VMError),
+}
+
+// One executed instruction, with the accumulator after it ran
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceEntry<I = Instruction> {
+    pub pc: usize,
+    pub instruction: I,
+    pub acc: i32,
+}
+
+impl<I: Op> fmt::Display for TraceEntry<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:4}  {}  acc={}", self.pc, self.instruction, self.acc)
+    }
+}
+
+// The instructions that repeat forever, starting from the one the VM refused
+// to run a second time
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub pcs: Vec<usize>,
+    pub acc_per_iteration: i32,
+}
+
+pub struct Debugger<I = Instruction> {
+    vm: VM<I>,
+    breakpoints: Vec<Breakpoint<I>>,
+    watch_acc: bool,
+    trace: Option<Vec<TraceEntry<I>>>,
+    // Where resume() last stopped at a breakpoint, so that resuming from
+    // there doesn't stop at the same breakpoint again
+    stopped_at: Option<usize>,
+}
+
+impl<I: Op> Debugger<I> {
+    pub fn new(vm: VM<I>) -> Self {
+        Debugger {
+            vm,
+            breakpoints: vec![],
+            watch_acc: false,
+            trace: None,
+            stopped_at: None,
+        }
+    }
+
+    pub fn vm(&self) -> &VM<I> {
+        &self.vm
+    }
+
+    pub fn into_vm(self) -> VM<I> {
+        self.vm
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint<I>) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint<I>) {
+        self.breakpoints.retain(|bp| *bp != breakpoint);
+    }
+
+    // Makes resume() stop after any instruction that changes acc
+    pub fn watch_acc(&mut self, watch: bool) {
+        self.watch_acc = watch;
+    }
+
+    // Starts recording every executed instruction, discarding any old trace
+    pub fn record_trace(&mut self) {
+        self.trace = Some(vec![]);
+    }
+
+    pub fn trace(&self) -> &[TraceEntry<I>] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn dump_trace(&self) -> String {
+        self.trace()
+            .iter()
+            .map(|entry| format!("{}\n", entry))
+            .collect()
+    }
+
+    // Executes exactly one instruction, ignoring breakpoints
+    pub fn step(&mut self) -> Result<Status, VMError> {
+        let pc = self.vm.pc();
+        let instruction = self.vm.program().get(pc);
+        let status = self.vm.step()?;
+        // Stepping at the end of the program executes nothing
+        if let (Some(trace), Some(instruction)) = (&mut self.trace, instruction) {
+            trace.push(TraceEntry {
+                pc,
+                instruction,
+                acc: self.vm.acc(),
+            });
+        }
+        Ok(status)
+    }
+
+    // Runs until a breakpoint, a change of acc if watched, or the end of the
+    // program. Breakpoints are checked before each instruction, except for the
+    // one resume() last stopped at, so that resuming from a breakpoint doesn't
+    // stop at the same place.
+    pub fn resume(&mut self) -> Stop<I> {
+        let mut skip = self.stopped_at.take().filter(|&pc| pc == self.vm.pc());
+        loop {
+            let pc = self.vm.pc();
+            if skip.take().is_none() {
+                if let Some(breakpoint) = self.breakpoints.iter().find(|bp| bp.hit(&self.vm)) {
+                    self.stopped_at = Some(pc);
+                    return Stop::Breakpoint(*breakpoint);
+                }
+            }
+            let old = self.vm.acc();
+            match self.step() {
+                Ok(Status::Finished) => return Stop::Finished,
+                Ok(Status::Running) => {}
+                Err(err) => return Stop::Halted(err),
+            }
+            let new = self.vm.acc();
+            if self.watch_acc && new != old {
+                return Stop::AccChanged { old, new };
+            }
+        }
+    }
+
+    // Starts the program over, keeping breakpoints and watches. The trace is
+    // cleared if one is being recorded.
+    pub fn restart(&mut self) {
+        self.vm.reset();
+        self.stopped_at = None;
+        if self.trace.is_some() {
+            self.record_trace();
+        }
+    }
+}
+
+// Finding the cycle relies on knowing where each instruction goes next, which
+// only the puzzle's instruction set does
+impl Debugger<Instruction> {
+    // After the VM halts with VMError::InfiniteLoop, it is about to execute an
+    // instruction already marked as visited. Following the program from there
+    // always leads back to it, so that path is the cycle.
+    pub fn cycle(&self) -> Option<Cycle> {
+        let start = self.vm.pc();
+        if !self.vm.visited(start) {
+            return None;
+        }
+        let program = self.vm.program();
+        let mut cycle = Cycle {
+            pcs: vec![],
+            acc_per_iteration: 0,
+        };
+        let mut pc = start;
+        loop {
+            let instruction = program.get(pc)?;
+            cycle.pcs.push(pc);
+            if let Instruction::ACC(value) = instruction {
+                cycle.acc_per_iteration += value as i32;
+            }
+            pc = instruction.next_pc(pc)?;
+            if pc == start {
+                return Some(cycle);
+            }
+            if cycle.pcs.len() > program.len() {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::program::Program;
+
+#[cfg(test)]
+fn example() -> Debugger {
+    let source = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+    Debugger::new(VM::new(Program::assemble(source).unwrap()))
+}
+
+#[test]
+fn test_breakpoints() {
+    let mut debugger = example();
+    debugger.add_breakpoint(Breakpoint::Pc(3));
+    debugger.add_breakpoint(Breakpoint::Instruction(Instruction::JMP(-4)));
+    assert_eq!(
+        debugger.resume(),
+        Stop::Breakpoint(Breakpoint::Instruction(Instruction::JMP(-4)))
+    );
+    assert_eq!((debugger.vm().pc(), debugger.vm().acc()), (7, 2));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Pc(3)));
+    debugger.remove_breakpoint(Breakpoint::Pc(3));
+    assert_eq!(debugger.resume(), Stop::Halted(VMError::InfiniteLoop));
+}
+
+#[test]
+fn test_breakpoint_at_start() {
+    let mut debugger = example();
+    debugger.add_breakpoint(Breakpoint::Pc(0));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Pc(0)));
+    assert_eq!((debugger.vm().pc(), debugger.vm().acc()), (0, 0));
+    // Resuming goes on from there, and the program never comes back to 0
+    assert_eq!(debugger.resume(), Stop::Halted(VMError::InfiniteLoop));
+    debugger.restart();
+    assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Pc(0)));
+}
+
+// A breakpoint on an instruction the program jumps back to is hit each time
+#[test]
+fn test_breakpoint_in_loop() {
+    let source = "acc +1\njmp -1";
+    let mut debugger: Debugger = Debugger::new(VM::new(Program::assemble(source).unwrap()));
+    debugger.add_breakpoint(Breakpoint::Pc(0));
+    assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Pc(0)));
+    assert_eq!(debugger.vm().acc(), 0);
+    assert_eq!(debugger.resume(), Stop::Breakpoint(Breakpoint::Pc(0)));
+    assert_eq!(debugger.vm().acc(), 1);
+}
+
+#[test]
+fn test_watch_and_trace() {
+    let mut debugger = example();
+    debugger.watch_acc(true);
+    debugger.record_trace();
+    assert_eq!(debugger.resume(), Stop::AccChanged { old: 0, new: 1 });
+    assert_eq!(debugger.step(), Ok(Status::Running));
+    assert_eq!(debugger.resume(), Stop::AccChanged { old: 1, new: 2 });
+    assert_eq!(
+        debugger.trace(),
+        [
+            TraceEntry {
+                pc: 0,
+                instruction: Instruction::NOP(0),
+                acc: 0
+            },
+            TraceEntry {
+                pc: 1,
+                instruction: Instruction::ACC(1),
+                acc: 1
+            },
+            TraceEntry {
+                pc: 2,
+                instruction: Instruction::JMP(4),
+                acc: 1
+            },
+            TraceEntry {
+                pc: 6,
+                instruction: Instruction::ACC(1),
+                acc: 2
+            },
+        ]
+    );
+    assert!(debugger.dump_trace().starts_with("   0  nop +0  acc=0\n"));
+    debugger.restart();
+    assert!(debugger.trace().is_empty());
+}
+
+#[test]
+fn test_cycle() {
+    let mut debugger = example();
+    assert_eq!(debugger.cycle(), None);
+    assert_eq!(debugger.resume(), Stop::Halted(VMError::InfiniteLoop));
+    assert_eq!(
+        debugger.cycle(),
+        Some(Cycle {
+            pcs: vec![1, 2, 6, 7, 3, 4],
+            acc_per_iteration: 5,
+        })
+    );
+}
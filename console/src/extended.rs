@@ -0,0 +1,164 @@
+// A richer instruction set built on the puzzle's: four registers a-d, where a
+// is the accumulator, conditional jumps, and input/output.
+//
+//     set r n     r = n
+//     add r s     r += s, wrapping around on overflow
+//     jz r off    jump by off if r is zero
+//     jnz r off   jump by off if r isn't zero
+//     in r        read the next input value into r
+//     out r       write r to the output
+//
+// Programs using jumps backwards usually execute some instructions more than
+// once, so they need to run with Halting::on_repeat turned off.
+
+use crate::op::{AsmError, Cpu, Op, Operands};
+use crate::program::Instruction;
+use crate::vm::VMError;
+use std::fmt;
+
+const REGISTER_NAMES: [&str; 4] = ["a", "b", "c", "d"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Extended {
+    Base(Instruction),
+    Set(usize, i16),
+    Add(usize, usize),
+    JumpIfZero(usize, i16),
+    JumpIfNonZero(usize, i16),
+    In(usize),
+    Out(usize),
+}
+
+impl Op for Extended {
+    const REGISTERS: usize = REGISTER_NAMES.len();
+
+    fn parse(mnemonic: &str, operands: &Operands) -> Result<Self, AsmError> {
+        let register = |ix| operands.register(ix, &REGISTER_NAMES);
+        let (instruction, count) = match mnemonic {
+            "set" => (Extended::Set(register(0)?, operands.number(1)?), 2),
+            "add" => (Extended::Add(register(0)?, register(1)?), 2),
+            "jz" => (Extended::JumpIfZero(register(0)?, operands.offset(1)?), 2),
+            "jnz" => (
+                Extended::JumpIfNonZero(register(0)?, operands.offset(1)?),
+                2,
+            ),
+            "in" => (Extended::In(register(0)?), 1),
+            "out" => (Extended::Out(register(0)?), 1),
+            _ => return Instruction::parse(mnemonic, operands).map(Extended::Base),
+        };
+        operands.finish(count)?;
+        Ok(instruction)
+    }
+
+    fn execute(self, cpu: &mut Cpu) -> Result<(), VMError> {
+        match self {
+            Extended::Base(instruction) => return instruction.execute(cpu),
+            Extended::Set(r, value) => cpu.registers[r] = value as i32,
+            Extended::Add(r, s) => {
+                cpu.registers[r] = cpu.registers[r].wrapping_add(cpu.registers[s]);
+            }
+            Extended::JumpIfZero(r, offset) if cpu.registers[r] == 0 => {
+                return cpu.jump(offset);
+            }
+            Extended::JumpIfNonZero(r, offset) if cpu.registers[r] != 0 => {
+                return cpu.jump(offset);
+            }
+            Extended::JumpIfZero(..) | Extended::JumpIfNonZero(..) => {}
+            Extended::In(r) => cpu.registers[r] = cpu.read()?,
+            Extended::Out(r) => cpu.write(cpu.registers[r]),
+        }
+        cpu.advance();
+        Ok(())
+    }
+}
+
+impl fmt::Display for Extended {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |r: &usize| REGISTER_NAMES[*r];
+        match self {
+            Extended::Base(instruction) => write!(f, "{}", instruction),
+            Extended::Set(r, value) => write!(f, "set {} {:+}", name(r), value),
+            Extended::Add(r, s) => write!(f, "add {} {}", name(r), name(s)),
+            Extended::JumpIfZero(r, offset) => write!(f, "jz {} {:+}", name(r), offset),
+            Extended::JumpIfNonZero(r, offset) => write!(f, "jnz {} {:+}", name(r), offset),
+            Extended::In(r) => write!(f, "in {}", name(r)),
+            Extended::Out(r) => write!(f, "out {}", name(r)),
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::program::Program;
+#[cfg(test)]
+use crate::vm::{Halting, VM};
+#[cfg(test)]
+use common::error::ParseError;
+
+#[test]
+fn test_sum_input() {
+    let source = "
+        loop: in b
+              jz b done
+              add a b
+              jmp loop
+        done: out a";
+    let program = Program::<Extended>::assemble(source).unwrap();
+    assert_eq!(
+        program.to_string(),
+        "in b\njz b +3\nadd a b\njmp -3\nout a\n"
+    );
+    assert_eq!(Program::assemble(&program.to_string()), Ok(program.clone()));
+
+    let halting = Halting {
+        on_repeat: false,
+        max_steps: Some(1000),
+    };
+    let mut vm = VM::new(program)
+        .with_halting(halting)
+        .with_input(vec![3, 4, 5, 0]);
+    assert_eq!(vm.run(), Ok(()));
+    assert_eq!(vm.output(), [12]);
+    assert_eq!(vm.registers(), [12, 0, 0, 0]);
+    vm.reset();
+    assert_eq!(vm.output(), []);
+    assert_eq!(vm.run(), Ok(()));
+    assert_eq!(vm.output(), [12]);
+
+    vm = vm.with_input(vec![1, 2]);
+    assert_eq!(vm.run(), Err(VMError::NoInput));
+}
+
+// Doubling a register overflows after 31 times round the loop, and after 32
+// it wraps around to 0
+#[test]
+fn test_add_wraps() {
+    let source = "set a +1\nset b +40\nset c -1\nadd a a\nadd b c\njnz b -2\nout a";
+    let program = Program::<Extended>::assemble(source).unwrap();
+    let halting = Halting {
+        on_repeat: false,
+        max_steps: Some(1000),
+    };
+    let mut vm = VM::new(program).with_halting(halting);
+    assert_eq!(vm.run(), Ok(()));
+    assert_eq!(vm.output(), [0]);
+}
+
+#[test]
+fn test_default_set_rejects_extensions() {
+    assert_eq!(
+        Program::<Instruction>::assemble("nop +0\nout a").err(),
+        Some(ParseError::new(2, 1, VMError::InvalidOpcode("out".into())))
+    );
+    assert_eq!(
+        Program::<Extended>::assemble("set e +1").err(),
+        Some(ParseError::new(1, 5, VMError::InvalidRegister("e".into())))
+    );
+    assert_eq!(
+        Program::<Extended>::assemble("jnz a").err(),
+        Some(ParseError::new(
+            1,
+            6,
+            VMError::InvalidParameter(String::new())
+        ))
+    );
+}
@@ -0,0 +1,35 @@
+//! The handheld game console from 2020 day 8: a program of NOP/ACC/JMP
+//! instructions and a VM to run it.
+//!
+//! `program::Program::assemble` turns source into a program, and `vm::VM`
+//! runs it one `step()` at a time or to the end with `run()`. By default the
+//! VM halts on the first repeated instruction; `with_halting` changes that to
+//! a step limit, or to both.
+//!
+//! Besides the puzzle's own format, the assembler accepts `#` comments and
+//! labels as jump targets; a program's `Display` disassembles it back to the
+//! canonical one-instruction-per-line text. `binary` encodes programs in
+//! three bytes per instruction.
+//!
+//! `debugger::Debugger` wraps a VM with breakpoints on an address or an
+//! instruction, a watch on the accumulator, a `(pc, instruction, acc)` trace,
+//! and after an infinite loop, the exact instructions that make up the cycle.
+//!
+//! Other instruction sets can be plugged in through the `op::Op` trait, which
+//! parses a mnemonic with its operands and executes on the registers, input
+//! and output of a `Cpu`. `extended::Extended` adds registers, conditional
+//! jumps and I/O.
+//!
+//! `analysis::find_repair` finds the one NOP or JMP to flip so that a program
+//! ends, without trying every flip: it works out which instructions lead to
+//! the end by walking the control-flow graph backwards, then follows the
+//! program once to find the flip that leads there.
+
+pub mod analysis;
+pub mod asm;
+pub mod binary;
+pub mod debugger;
+pub mod extended;
+pub mod op;
+pub mod program;
+pub mod vm;
@@ -0,0 +1,161 @@
+// The extension point for instruction sets. The VM, the assembler and the
+// debugger work with any type implementing Op; program::Instruction, with the
+// puzzle's nop, acc and jmp, is the default, and extended::Extended shows how
+// to add more.
+
+use crate::program::checked_jump;
+use crate::vm::VMError;
+use std::fmt;
+
+// The machine state an instruction can see and change. Register 0 is the
+// accumulator.
+#[derive(Clone, Debug, Default)]
+pub struct Cpu {
+    pub pc: usize,
+    pub registers: Vec<i32>,
+    pub output: Vec<i32>,
+    input: Vec<i32>,
+    input_read: usize,
+}
+
+impl Cpu {
+    pub(crate) fn new(n_registers: usize, input: Vec<i32>) -> Self {
+        Cpu {
+            registers: vec![0; n_registers],
+            input,
+            ..Default::default()
+        }
+    }
+
+    // Back to the start, with the same input still to be read
+    pub(crate) fn reset(&mut self) {
+        self.pc = 0;
+        self.registers.iter_mut().for_each(|register| *register = 0);
+        self.output.clear();
+        self.input_read = 0;
+    }
+
+    pub fn advance(&mut self) {
+        self.pc += 1;
+    }
+
+    pub fn jump(&mut self, offset: i16) -> Result<(), VMError> {
+        self.pc = checked_jump(self.pc, offset).ok_or(VMError::InvalidJump)?;
+        Ok(())
+    }
+
+    pub fn read(&mut self) -> Result<i32, VMError> {
+        let value = *self.input.get(self.input_read).ok_or(VMError::NoInput)?;
+        self.input_read += 1;
+        Ok(value)
+    }
+
+    pub fn write(&mut self, value: i32) {
+        self.output.push(value);
+    }
+}
+
+pub trait Op: Copy + PartialEq + fmt::Debug + fmt::Display {
+    const REGISTERS: usize = 1;
+
+    // Builds the instruction from its mnemonic and operands, the way it is
+    // written in the assembler (see the asm module)
+    fn parse(mnemonic: &str, operands: &Operands) -> Result<Self, AsmError>;
+
+    // Executes the instruction at cpu.pc, which must leave cpu.pc pointing at
+    // the next instruction to execute
+    fn execute(self, cpu: &mut Cpu) -> Result<(), VMError>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AsmError {
+    UnknownOpcode,
+    // Which operand is wrong, counting from 0
+    Operand(usize, VMError),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Operand {
+    Number(i64),
+    // A label, already resolved to an offset from the instruction using it
+    Label(i64),
+    Name,
+}
+
+// The operands of one instruction in the assembler, as written and as
+// interpreted so far
+pub struct Operands<'a> {
+    tokens: &'a [&'a str],
+    values: &'a [Operand],
+}
+
+impl<'a> Operands<'a> {
+    pub(crate) fn new(tokens: &'a [&'a str], values: &'a [Operand]) -> Self {
+        Operands { tokens, values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn text(&self, ix: usize) -> Result<&'a str, AsmError> {
+        self.tokens
+            .get(ix)
+            .copied()
+            .ok_or_else(|| AsmError::Operand(ix, VMError::InvalidParameter(String::new())))
+    }
+
+    fn bad(&self, ix: usize) -> AsmError {
+        AsmError::Operand(ix, VMError::InvalidParameter(self.tokens[ix].into()))
+    }
+
+    // A number, which must fit in T
+    pub fn number<T: TryFrom<i64>>(&self, ix: usize) -> Result<T, AsmError> {
+        self.text(ix)?;
+        match self.values[ix] {
+            Operand::Number(n) => T::try_from(n).map_err(|_| self.bad(ix)),
+            _ => Err(self.bad(ix)),
+        }
+    }
+
+    // A jump offset, either as a number or as a label
+    pub fn offset(&self, ix: usize) -> Result<i16, AsmError> {
+        let text = self.text(ix)?;
+        match self.values[ix] {
+            Operand::Number(n) | Operand::Label(n) => n.try_into().map_err(|_| self.bad(ix)),
+            Operand::Name if is_label(text) => {
+                Err(AsmError::Operand(ix, VMError::UndefinedLabel(text.into())))
+            }
+            Operand::Name => Err(self.bad(ix)),
+        }
+    }
+
+    // One of the named registers, as an index into Cpu::registers
+    pub fn register(&self, ix: usize, names: &[&str]) -> Result<usize, AsmError> {
+        let text = self.text(ix)?;
+        names
+            .iter()
+            .position(|name| *name == text)
+            .ok_or_else(|| AsmError::Operand(ix, VMError::InvalidRegister(text.into())))
+    }
+
+    // Checks that there are no more than `count` operands
+    pub fn finish(&self, count: usize) -> Result<(), AsmError> {
+        match self.tokens.get(count) {
+            Some(extra) => Err(AsmError::Operand(
+                count,
+                VMError::UnexpectedToken((*extra).into()),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+pub(crate) fn is_label(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
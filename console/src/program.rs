@@ -0,0 +1,176 @@
+use crate::asm;
+use crate::op::{AsmError, Cpu, Op, Operands};
+use crate::vm::VMError;
+use common::error::ParseError;
+use std::ops::{Index, IndexMut};
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    NOP(i16),
+    ACC(i16),
+    JMP(i16),
+}
+
+impl Instruction {
+    // The instruction with NOP and JMP swapped, or None for ACC
+    pub fn flipped(self) -> Option<Instruction> {
+        match self {
+            Instruction::NOP(param) => Some(Instruction::JMP(param)),
+            Instruction::ACC(_) => None,
+            Instruction::JMP(param) => Some(Instruction::NOP(param)),
+        }
+    }
+
+    // Where execution continues after this instruction runs at `pc`, or None
+    // if a jump would go below 0
+    pub fn next_pc(self, pc: usize) -> Option<usize> {
+        match self {
+            Instruction::NOP(_) | Instruction::ACC(_) => Some(pc + 1),
+            Instruction::JMP(distance) => checked_jump(pc, distance),
+        }
+    }
+}
+
+impl Op for Instruction {
+    fn parse(mnemonic: &str, operands: &Operands) -> Result<Self, AsmError> {
+        let instruction = match mnemonic {
+            "nop" => Instruction::NOP(operands.offset(0)?),
+            "acc" => Instruction::ACC(operands.number(0)?),
+            "jmp" => Instruction::JMP(operands.offset(0)?),
+            _ => return Err(AsmError::UnknownOpcode),
+        };
+        operands.finish(1)?;
+        Ok(instruction)
+    }
+
+    fn execute(self, cpu: &mut Cpu) -> Result<(), VMError> {
+        match self {
+            Instruction::NOP(_) => cpu.advance(),
+            Instruction::ACC(value) => {
+                cpu.registers[0] = cpu.registers[0].wrapping_add(value as i32);
+                cpu.advance();
+            }
+            Instruction::JMP(offset) => cpu.jump(offset)?,
+        }
+        Ok(())
+    }
+}
+
+// https://stackoverflow.com/a/54035801/172999
+pub(crate) fn checked_jump(pc: usize, jump: i16) -> Option<usize> {
+    if jump.is_negative() {
+        pc.checked_sub(jump.wrapping_abs() as u16 as usize)
+    } else {
+        pc.checked_add(jump as usize)
+    }
+}
+
+// A program for the instruction set I, which is the puzzle's unless given
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Program<I = Instruction> {
+    code: Vec<I>,
+}
+
+impl<I: Op> Program<I> {
+    // See the asm module for the syntax
+    pub fn assemble(source: &str) -> Result<Self, ParseError<VMError>> {
+        asm::assemble(source)
+    }
+
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+
+    pub fn get(&self, pc: usize) -> Option<I> {
+        self.code.get(pc).copied()
+    }
+
+    pub fn instructions(&self) -> &[I] {
+        &self.code
+    }
+}
+
+impl<I> From<Vec<I>> for Program<I> {
+    fn from(code: Vec<I>) -> Self {
+        Program { code }
+    }
+}
+
+impl<I> Index<usize> for Program<I> {
+    type Output = I;
+
+    fn index(&self, pc: usize) -> &I {
+        &self.code[pc]
+    }
+}
+
+impl<I> IndexMut<usize> for Program<I> {
+    fn index_mut(&mut self, pc: usize) -> &mut I {
+        &mut self.code[pc]
+    }
+}
+
+#[test]
+fn test_assemble() {
+    let program = Program::<Instruction>::assemble("nop +0\nacc -99\njmp +4").unwrap();
+    assert_eq!(
+        program.instructions(),
+        [
+            Instruction::NOP(0),
+            Instruction::ACC(-99),
+            Instruction::JMP(4)
+        ]
+    );
+    assert_eq!(program[2].flipped(), Some(Instruction::NOP(4)));
+    assert_eq!(program[1].flipped(), None);
+}
+
+#[test]
+fn test_assemble_errors() {
+    assert_eq!(
+        Program::<Instruction>::assemble("nop +0\nacc +1\njmp +x"),
+        Err(ParseError::new(
+            3,
+            5,
+            VMError::InvalidParameter("+x".into())
+        ))
+    );
+    assert_eq!(
+        Program::<Instruction>::assemble("nop +0\nhcf +1"),
+        Err(ParseError::new(2, 1, VMError::InvalidOpcode("hcf".into())))
+    );
+}
+
+// A deterministic pseudo-random program, for round-trip tests
+#[cfg(test)]
+pub(crate) fn random_program(seed: u64) -> Program {
+    let mut state = seed;
+    let mut next = move || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let len = next() % 64;
+    (0..len)
+        .map(|_| {
+            let random = next();
+            let param = match random % 3 {
+                0 => (random >> 8) as i16,
+                _ => (random >> 8) as i16 % 16,
+            };
+            match (random >> 2) % 3 {
+                0 => Instruction::NOP(param),
+                1 => Instruction::ACC(param),
+                _ => Instruction::JMP(param),
+            }
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
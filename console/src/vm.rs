@@ -0,0 +1,247 @@
+use crate::op::{Cpu, Op};
+use crate::program::{Instruction, Program};
+use bitvec::prelude::*;
+use std::cmp::Ordering;
+use std::convert;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug, PartialEq)]
+pub enum VMError {
+    InvalidOpcode(String),
+    InvalidParameter(String),
+    InvalidLabel(String),
+    DuplicateLabel(String),
+    UndefinedLabel(String),
+    UnexpectedToken(String),
+    InvalidRegister(String),
+    TruncatedBinary,
+    InvalidJump,
+    PastTheEnd,
+    InfiniteLoop,
+    StepLimit(usize),
+    NoInput,
+}
+
+impl fmt::Display for VMError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VMError::InvalidOpcode(opcode) => write!(f, "Unknown opcode {}", opcode),
+            VMError::InvalidParameter(param) => {
+                write!(f, "Parameter {} not a 16-bit integer", param)
+            }
+            VMError::InvalidLabel(label) => write!(f, "Invalid label name {:?}", label),
+            VMError::DuplicateLabel(label) => write!(f, "Label {} defined twice", label),
+            VMError::UndefinedLabel(label) => write!(f, "Undefined label {}", label),
+            VMError::UnexpectedToken(token) => write!(f, "Unexpected {:?}", token),
+            VMError::InvalidRegister(name) => write!(f, "Unknown register {:?}", name),
+            VMError::TruncatedBinary => {
+                write!(f, "Binary program ends partway through an instruction")
+            }
+            VMError::InvalidJump => write!(f, "Negative jump overflow"),
+            VMError::PastTheEnd => write!(f, "Positive jump overflow"),
+            VMError::InfiniteLoop => write!(f, "Infinite loop detected"),
+            VMError::StepLimit(steps) => write!(f, "Still running after {} steps", steps),
+            VMError::NoInput => write!(f, "Read past the end of the input"),
+        }
+    }
+}
+
+impl Error for VMError {}
+
+impl convert::From<VMError> for io::Error {
+    fn from(err: VMError) -> io::Error {
+        io::Error::other(err)
+    }
+}
+
+// When the VM gives up on a program that hasn't reached its end. By default it
+// stops as soon as it is about to execute an instruction a second time, which
+// for this instruction set means it would loop forever.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Halting {
+    pub on_repeat: bool,
+    pub max_steps: Option<usize>,
+}
+
+impl Default for Halting {
+    fn default() -> Self {
+        Halting {
+            on_repeat: true,
+            max_steps: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Running,
+    Finished,
+}
+
+#[derive(Clone, Debug)]
+pub struct VM<I = Instruction> {
+    program: Program<I>,
+    halting: Halting,
+    cpu: Cpu,
+    steps: usize,
+    visited: BitVec,
+}
+
+impl<I: Op> VM<I> {
+    pub fn new(program: Program<I>) -> Self {
+        let visited = bitvec![0; program.len()];
+        VM {
+            program,
+            halting: Halting::default(),
+            cpu: Cpu::new(I::REGISTERS, vec![]),
+            steps: 0,
+            visited,
+        }
+    }
+
+    pub fn with_halting(mut self, halting: Halting) -> Self {
+        self.halting = halting;
+        self
+    }
+
+    // Values for the program to read, in order. They are read again from the
+    // start every time the VM is reset.
+    pub fn with_input(mut self, input: Vec<i32>) -> Self {
+        self.cpu = Cpu::new(I::REGISTERS, input);
+        self
+    }
+
+    pub fn acc(&self) -> i32 {
+        self.cpu.registers[0]
+    }
+
+    pub fn registers(&self) -> &[i32] {
+        &self.cpu.registers
+    }
+
+    pub fn output(&self) -> &[i32] {
+        &self.cpu.output
+    }
+
+    pub fn pc(&self) -> usize {
+        self.cpu.pc
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn program(&self) -> &Program<I> {
+        &self.program
+    }
+
+    // Changing the program doesn't reset the VM; call reset() or run() after
+    pub fn program_mut(&mut self) -> &mut Program<I> {
+        &mut self.program
+    }
+
+    // Whether the instruction at `pc` has executed since the last reset
+    pub fn visited(&self, pc: usize) -> bool {
+        self.visited.get(pc).is_some_and(|bit| *bit)
+    }
+
+    pub fn reset(&mut self) {
+        self.cpu.reset();
+        self.steps = 0;
+        self.visited.set_all(false);
+    }
+
+    // Executes one instruction. Finished means pc is just past the last
+    // instruction, which is the only way for a program to end normally.
+    pub fn step(&mut self) -> Result<Status, VMError> {
+        let pc = self.cpu.pc;
+        let instruction = match self.program.get(pc) {
+            Some(instruction) => instruction,
+            None if pc == self.program.len() => return Ok(Status::Finished),
+            None => return Err(VMError::PastTheEnd),
+        };
+        if self.halting.on_repeat && self.visited[pc] {
+            return Err(VMError::InfiniteLoop);
+        }
+        if self.halting.max_steps == Some(self.steps) {
+            return Err(VMError::StepLimit(self.steps));
+        }
+        self.visited.set(pc, true);
+        self.steps += 1;
+        instruction.execute(&mut self.cpu)?;
+        match self.cpu.pc.cmp(&self.program.len()) {
+            Ordering::Less => Ok(Status::Running),
+            Ordering::Equal => Ok(Status::Finished),
+            Ordering::Greater => Err(VMError::PastTheEnd),
+        }
+    }
+
+    // Steps from the current state until the program ends or halts
+    pub fn resume(&mut self) -> Result<(), VMError> {
+        while self.step()? == Status::Running {}
+        Ok(())
+    }
+
+    // Runs the program from the start
+    pub fn run(&mut self) -> Result<(), VMError> {
+        self.reset();
+        self.resume()
+    }
+}
+
+#[cfg(test)]
+static EXAMPLE: &str = "nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+#[cfg(test)]
+fn assembled(source: &str) -> VM {
+    VM::new(Program::assemble(source).unwrap())
+}
+
+#[test]
+fn test_run() {
+    let mut vm = assembled(EXAMPLE);
+    assert_eq!(vm.run(), Err(VMError::InfiniteLoop));
+    assert_eq!((vm.acc(), vm.pc()), (5, 1));
+    assert!(vm.visited(4) && !vm.visited(5));
+
+    vm.program_mut()[7] = Instruction::NOP(-4);
+    assert_eq!(vm.run(), Ok(()));
+    assert_eq!((vm.acc(), vm.steps()), (8, 6));
+}
+
+#[test]
+fn test_step() {
+    let mut vm = assembled("acc +2\njmp -1\nacc +1");
+    assert_eq!(vm.step(), Ok(Status::Running));
+    assert_eq!((vm.acc(), vm.pc()), (2, 1));
+    assert_eq!(vm.step(), Ok(Status::Running));
+    assert_eq!(vm.step(), Err(VMError::InfiniteLoop));
+
+    let mut vm = assembled("jmp +2\nnop +0");
+    assert_eq!(vm.step(), Ok(Status::Finished));
+    assert_eq!(vm.step(), Ok(Status::Finished));
+    let mut vm = assembled("jmp +3\nnop +0");
+    assert_eq!(vm.step(), Err(VMError::PastTheEnd));
+    let mut vm = assembled("jmp -1");
+    assert_eq!(vm.step(), Err(VMError::InvalidJump));
+}
+
+#[test]
+fn test_halting() {
+    let mut vm = assembled("acc +1\njmp -1").with_halting(Halting {
+        on_repeat: false,
+        max_steps: Some(100),
+    });
+    assert_eq!(vm.run(), Err(VMError::StepLimit(100)));
+    assert_eq!(vm.acc(), 50);
+}
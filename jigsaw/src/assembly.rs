@@ -0,0 +1,417 @@
+// Puts tiles together greedily: starting from a corner, each tile placed
+// decides the next one, because no border is shared by more than two tiles.
+
+use crate::orientation::Orientation;
+use crate::tile::{canonical, Border, Side, Tile};
+use ndarray::{concatenate, Array2, Axis};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum JigsawError {
+    Empty,
+    BadShape {
+        id: u64,
+        rows: usize,
+        columns: usize,
+    },
+    SizeMismatch {
+        id: u64,
+        expected: usize,
+        got: usize,
+    },
+    DuplicateId(u64),
+    // A border of this tile matches more than one other tile
+    Ambiguous(u64),
+    // Nothing fits at this position in the grid
+    NoFit {
+        row: usize,
+        column: usize,
+    },
+    // The first row is this wide, which doesn't divide the number of tiles
+    Ragged {
+        width: usize,
+        tiles: usize,
+    },
+    // The tiles don't go together in any grid
+    NoAssembly,
+    // The tiles go together in this many ways, not counting turning or
+    // flipping the whole picture
+    Multiple(usize),
+}
+
+impl fmt::Display for JigsawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JigsawError::Empty => write!(f, "No tiles to put together"),
+            JigsawError::BadShape { id, rows, columns } => write!(
+                f,
+                "Tile {} is {}x{}, expected a square of side at least 3",
+                id, rows, columns
+            ),
+            JigsawError::SizeMismatch { id, expected, got } => write!(
+                f,
+                "Tile {} has side {}, but the first tile has side {}",
+                id, got, expected
+            ),
+            JigsawError::DuplicateId(id) => write!(f, "More than one tile {}", id),
+            JigsawError::Ambiguous(id) => {
+                write!(f, "Tile {} could fit next to more than one tile", id)
+            }
+            JigsawError::NoFit { row, column } => {
+                write!(f, "No tile fits at row {}, column {}", row, column)
+            }
+            JigsawError::Ragged { width, tiles } => write!(
+                f,
+                "The first row is {} tiles wide, but there are {} tiles",
+                width, tiles
+            ),
+            JigsawError::NoAssembly => write!(f, "The tiles don't fit together"),
+            JigsawError::Multiple(count) => {
+                write!(f, "The tiles fit together in {} different ways", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JigsawError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub tile_id: u64,
+    pub orientation: Orientation,
+}
+
+#[derive(Clone, Debug)]
+pub struct Assembly {
+    tiles: HashMap<u64, Tile>,
+    placements: Array2<Placement>,
+}
+
+impl Assembly {
+    pub(crate) fn new(tiles: &[Tile], placements: Array2<Placement>) -> Self {
+        Assembly {
+            tiles: tiles.iter().map(|tile| (tile.id(), tile.clone())).collect(),
+            placements,
+        }
+    }
+
+    pub fn placements(&self) -> &Array2<Placement> {
+        &self.placements
+    }
+
+    pub fn tile(&self, id: u64) -> Option<&Tile> {
+        self.tiles.get(&id)
+    }
+
+    // Clockwise from the top left
+    pub fn corners(&self) -> [u64; 4] {
+        let (rows, columns) = self.placements.dim();
+        [
+            (0, 0),
+            (0, columns - 1),
+            (rows - 1, columns - 1),
+            (rows - 1, 0),
+        ]
+        .map(|index| self.placements[index].tile_id)
+    }
+
+    // The insides of all the tiles, without their borders, put together
+    pub fn image(&self) -> Array2<u8> {
+        let images = self.placements.map(|placement| {
+            self.tiles[&placement.tile_id]
+                .image(placement.orientation)
+                .into_owned()
+        });
+        let rows: Vec<_> = images
+            .outer_iter()
+            .map(|row| {
+                let views: Vec<_> = row.iter().map(|image| image.view()).collect();
+                concatenate(Axis(1), &views).unwrap()
+            })
+            .collect();
+        let views: Vec<_> = rows.iter().map(|row| row.view()).collect();
+        concatenate(Axis(0), &views).unwrap()
+    }
+}
+
+// Which tiles have each border, in either direction
+pub(crate) struct BorderIndex {
+    tiles: HashMap<Border, Vec<u64>>,
+}
+
+impl BorderIndex {
+    pub(crate) fn new(tiles: &[Tile]) -> Self {
+        let mut index: HashMap<_, Vec<_>> = HashMap::new();
+        for tile in tiles {
+            for border in tile.borders(Orientation::IDENTITY) {
+                index.entry(canonical(&border)).or_default().push(tile.id());
+            }
+        }
+        BorderIndex { tiles: index }
+    }
+
+    // The other tiles with this border
+    pub(crate) fn others(&self, id: u64, border: &[u8]) -> impl Iterator<Item = u64> + '_ {
+        self.tiles[&canonical(border)]
+            .iter()
+            .copied()
+            .filter(move |&other| other != id)
+    }
+
+    pub(crate) fn is_edge(&self, id: u64, border: &[u8]) -> bool {
+        self.others(id, border).next().is_none()
+    }
+
+    // The number of sides of a tile that another tile could fit against
+    #[cfg(test)]
+    pub(crate) fn matched_sides(&self, tile: &Tile) -> usize {
+        tile.borders(Orientation::IDENTITY)
+            .iter()
+            .filter(|border| !self.is_edge(tile.id(), border))
+            .count()
+    }
+}
+
+// Checks that the tiles are all the same size, with different IDs
+pub(crate) fn check_tiles(tiles: &[Tile]) -> Result<(), JigsawError> {
+    let first = tiles.first().ok_or(JigsawError::Empty)?;
+    let mut ids = HashSet::new();
+    for tile in tiles {
+        if tile.size() != first.size() {
+            return Err(JigsawError::SizeMismatch {
+                id: tile.id(),
+                expected: first.size(),
+                got: tile.size(),
+            });
+        }
+        if !ids.insert(tile.id()) {
+            return Err(JigsawError::DuplicateId(tile.id()));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn reversed(border: &[u8]) -> Border {
+    border.iter().rev().copied().collect()
+}
+
+// How the tile has to lie for its top and left borders to be these, read
+// clockwise around the tile; None for no constraint
+fn orient(tile: &Tile, top: Option<&[u8]>, left: Option<&[u8]>) -> Option<Orientation> {
+    Orientation::all().find(|&orientation| {
+        top.is_none_or(|top| tile.border(orientation, Side::Top) == top)
+            && left.is_none_or(|left| tile.border(orientation, Side::Left) == left)
+    })
+}
+
+pub fn assemble(tiles: &[Tile]) -> Result<Assembly, JigsawError> {
+    check_tiles(tiles)?;
+    let index = BorderIndex::new(tiles);
+    if let Some(tile) = tiles.iter().find(|tile| {
+        tile.borders(Orientation::IDENTITY)
+            .iter()
+            .any(|border| index.others(tile.id(), border).count() > 1)
+    }) {
+        return Err(JigsawError::Ambiguous(tile.id()));
+    }
+    let by_id: HashMap<_, _> = tiles.iter().map(|tile| (tile.id(), tile)).collect();
+
+    // A corner, turned so that nothing fits above it or to its left
+    let (corner, orientation) = tiles
+        .iter()
+        .find_map(|tile| {
+            let orientation = Orientation::all().find(|&orientation| {
+                index.is_edge(tile.id(), &tile.border(orientation, Side::Top))
+                    && index.is_edge(tile.id(), &tile.border(orientation, Side::Left))
+            })?;
+            Some((tile, orientation))
+        })
+        .ok_or(JigsawError::NoFit { row: 0, column: 0 })?;
+    let mut used = HashSet::from([corner.id()]);
+    let mut placements = vec![Placement {
+        tile_id: corner.id(),
+        orientation,
+    }];
+
+    // The tile that fits against one side of a placed tile, unless that side
+    // is an edge of the picture
+    let next = |placement: &Placement, side: Side| {
+        let border = by_id[&placement.tile_id].border(placement.orientation, side);
+        let other = index.others(placement.tile_id, &border).next()?;
+        Some((by_id[&other], reversed(&border)))
+    };
+    let mut place = |tile: &Tile, orientation: Option<Orientation>, row, column| {
+        let orientation = orientation.filter(|_| used.insert(tile.id()));
+        let orientation = orientation.ok_or(JigsawError::NoFit { row, column })?;
+        Ok(Placement {
+            tile_id: tile.id(),
+            orientation,
+        })
+    };
+
+    // The first row goes on until a tile's right side is an edge
+    while let Some((tile, left)) = next(placements.last().unwrap(), Side::Right) {
+        let column = placements.len();
+        placements.push(place(tile, orient(tile, None, Some(&left)), 0, column)?);
+    }
+    let width = placements.len();
+    if !tiles.len().is_multiple_of(width) {
+        return Err(JigsawError::Ragged {
+            width,
+            tiles: tiles.len(),
+        });
+    }
+    for ix in width..tiles.len() {
+        let (row, column) = (ix / width, ix % width);
+        let no_fit = JigsawError::NoFit { row, column };
+        let (tile, top) = next(&placements[ix - width], Side::Bottom).ok_or(no_fit)?;
+        let orientation = if column == 0 {
+            orient(tile, Some(&top), None)
+        } else {
+            let left = reversed(
+                &by_id[&placements[ix - 1].tile_id]
+                    .border(placements[ix - 1].orientation, Side::Right),
+            );
+            orient(tile, Some(&top), Some(&left))
+        };
+        placements.push(place(tile, orientation, row, column)?);
+    }
+
+    let height = tiles.len() / width;
+    let placements = Array2::from_shape_vec((height, width), placements).unwrap();
+    Ok(Assembly::new(tiles, placements))
+}
+
+// Reads tiles in the puzzle's format, "Tile <id>:" and then rows of # and .
+#[cfg(test)]
+pub(crate) fn read_tiles(input: &str) -> Vec<Tile> {
+    input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let header = lines.next().unwrap();
+            let id = header["Tile ".len()..header.len() - 1].parse().unwrap();
+            let rows: Vec<_> = lines.collect();
+            let pixels: Vec<_> = rows
+                .iter()
+                .flat_map(|row| row.bytes().map(|b| (b == b'#') as u8))
+                .collect();
+            let size = rows.len();
+            Tile::new(id, Array2::from_shape_vec((size, size), pixels).unwrap()).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) const EXAMPLE: &str = include_str!("../../puzzle20/test_input");
+
+#[test]
+fn test_example() {
+    let tiles = read_tiles(EXAMPLE);
+    assert_eq!(
+        tiles.iter().map(|t| t.id()).collect::<Vec<u64>>(),
+        [2311, 1951, 1171, 1427, 1489, 2473, 2971, 2729, 3079]
+    );
+    let index = BorderIndex::new(&tiles);
+    assert_eq!(
+        tiles
+            .iter()
+            .map(|tile| index.matched_sides(tile))
+            .collect::<Vec<_>>(),
+        [3, 2, 2, 4, 3, 3, 2, 3, 2]
+    );
+
+    let assembly = assemble(&tiles).unwrap();
+    assert_eq!(assembly.placements().dim(), (3, 3));
+    let mut corners = assembly.corners();
+    corners.sort_unstable();
+    assert_eq!(corners, [1171, 1951, 2971, 3079]);
+    assert_eq!(assembly.placements()[[1, 1]].tile_id, 1427);
+    let image = assembly.image();
+    assert_eq!(image.dim(), (24, 24));
+    assert_eq!(image.iter().filter(|&&pixel| pixel != 0).count(), 303);
+
+    // Neighbouring tiles agree along the borders they share
+    let placements = assembly.placements();
+    for ((row, column), placement) in placements.indexed_iter() {
+        let tile = assembly.tile(placement.tile_id).unwrap();
+        if let Some(right) = placements.get((row, column + 1)) {
+            let other = assembly.tile(right.tile_id).unwrap();
+            assert_eq!(
+                tile.border(placement.orientation, Side::Right),
+                reversed(&other.border(right.orientation, Side::Left))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_errors() {
+    let tiles = read_tiles(EXAMPLE);
+    assert_eq!(assemble(&[]).err(), Some(JigsawError::Empty));
+    assert_eq!(
+        assemble(&tiles[2..]).err(),
+        Some(JigsawError::Ragged { width: 3, tiles: 7 })
+    );
+    // Without 2311, the picture looks like it goes on past an edge
+    assert_eq!(
+        assemble(&tiles[1..]).err(),
+        Some(JigsawError::NoFit { row: 3, column: 0 })
+    );
+    let mut duplicated = tiles.clone();
+    duplicated.push(tiles[0].clone());
+    assert!(matches!(
+        assemble(&duplicated).err(),
+        Some(JigsawError::DuplicateId(2311))
+    ));
+    let big = Tile::new(5, Array2::zeros((11, 11))).unwrap();
+    assert_eq!(
+        assemble(&[tiles[0].clone(), big]).err(),
+        Some(JigsawError::SizeMismatch {
+            id: 5,
+            expected: 10,
+            got: 11
+        })
+    );
+}
+
+// Tiles of 20x20 pixels, with borders too wide for 16 bits, cut out of a
+// picture so that neighbouring tiles share a line of pixels
+#[test]
+fn test_big_tiles() {
+    let (side, rows, columns) = (20, 3, 2);
+    let step = side - 1;
+    let picture = Array2::from_shape_fn((rows * step + 1, columns * step + 1), |(y, x)| {
+        // splitmix64, for pixels that don't repeat
+        let mut hash = ((y as u64) << 32 | x as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((hash ^ (hash >> 31)) & 1) as u8
+    });
+    let mut tiles = vec![];
+    for row in 0..rows {
+        for column in 0..columns {
+            let (y, x) = (row * step, column * step);
+            let pixels = picture.slice(ndarray::s![y..y + side, x..x + side]);
+            let turned = Orientation {
+                flipped: row == 1,
+                turns: (row + column) as u8,
+            };
+            let id = (10 * row + column) as u64;
+            tiles.push(Tile::new(id, turned.apply(pixels).into_owned()).unwrap());
+        }
+    }
+    tiles.reverse();
+
+    let image = assemble(&tiles).unwrap().image();
+    let keep = |n: usize| !n.is_multiple_of(step);
+    let inside: Vec<_> = picture
+        .indexed_iter()
+        .filter(|((y, x), _)| keep(*y) && keep(*x))
+        .map(|(_, &pixel)| pixel)
+        .collect();
+    let inside = Array2::from_shape_vec((rows * (step - 1), columns * (step - 1)), inside).unwrap();
+    assert!(Orientation::all().any(|orientation| orientation.apply(image.view()) == inside));
+}
@@ -0,0 +1,24 @@
+//! Square tiles put together into one big picture, as in 2020 day 20: tiles
+//! can be turned and flipped, and fit next to each other where their borders
+//! match. The tiles can be any size.
+//!
+//! A `tile::Tile` is a square of pixels, and `assembly::assemble` lays tiles
+//! out in a grid of `Placement`s, each a tile ID and one of the eight
+//! `Orientation`s. Tiles that don't go together, or that could go together in
+//! more than one way, give a `JigsawError`.
+//!
+//! `assemble` relies on each border matching at most one other tile. When that
+//! isn't so, `search::assemble_all` backtracks through every tile that fits at
+//! each position and returns each distinct assembly, counting the same picture
+//! turned or flipped once. `search::assemble_unique` fails unless exactly one
+//! assembly exists, saying how many there are if there are more.
+//!
+//! `pattern::Pattern` is a shape to look for in a picture, drawn in ASCII with
+//! `#` for pixels that must be set. `Pattern::find` looks for it turned and
+//! flipped all eight ways, counting overlapping matches or only separate ones.
+
+pub mod assembly;
+pub mod orientation;
+pub mod pattern;
+pub mod search;
+pub mod tile;
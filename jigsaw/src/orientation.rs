@@ -0,0 +1,113 @@
+use ndarray::{s, ArrayView2};
+use std::fmt;
+
+// One of the eight ways to lay a square tile down: flipped left to right or
+// not, and then turned anticlockwise by some quarter turns
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub flipped: bool,
+    pub turns: u8,
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation {
+        flipped: false,
+        turns: 0,
+    };
+
+    // Unflipped ones first
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true]
+            .into_iter()
+            .flat_map(|flipped| (0..4).map(move |turns| Orientation { flipped, turns }))
+    }
+
+    // The orientation that does this one and then `other`
+    pub fn then(self, other: Orientation) -> Orientation {
+        // A flip followed by turns anticlockwise is the same as the turns
+        // clockwise followed by the flip
+        let turns = if other.flipped {
+            other.turns + 4 - self.turns % 4
+        } else {
+            other.turns + self.turns
+        };
+        Orientation {
+            flipped: self.flipped != other.flipped,
+            turns: turns % 4,
+        }
+    }
+
+    // The orientation that undoes this one
+    pub fn inverse(self) -> Orientation {
+        Orientation::all()
+            .find(|&other| self.then(other) == Orientation::IDENTITY)
+            .unwrap()
+    }
+
+    pub fn apply<'a, A>(self, view: ArrayView2<'a, A>) -> ArrayView2<'a, A> {
+        let view = if self.flipped {
+            view.slice_move(s![.., ..;-1])
+        } else {
+            view
+        };
+        (0..self.turns % 4).fold(view, |view, _| {
+            view.slice_move(s![.., ..;-1]).reversed_axes()
+        })
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}°", 90 * (self.turns % 4) as u16)?;
+        if self.flipped {
+            write!(f, " flipped")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use ndarray::array;
+
+#[test]
+fn test_apply() {
+    let grid = array![[1, 2], [3, 4]];
+    let turn = |turns| Orientation {
+        flipped: false,
+        turns,
+    };
+    assert_eq!(Orientation::IDENTITY.apply(grid.view()), grid);
+    assert_eq!(turn(1).apply(grid.view()), array![[2, 4], [1, 3]]);
+    assert_eq!(turn(2).apply(grid.view()), array![[4, 3], [2, 1]]);
+    let flip = Orientation {
+        flipped: true,
+        turns: 1,
+    };
+    assert_eq!(flip.apply(grid.view()), array![[1, 3], [2, 4]]);
+    assert_eq!(flip.to_string(), "90° flipped");
+
+    let mut all: Vec<_> = Orientation::all()
+        .map(|orientation| orientation.apply(grid.view()).into_owned())
+        .collect();
+    assert_eq!(all.len(), 8);
+    all.sort_by_key(|grid| grid.iter().copied().collect::<Vec<_>>());
+    all.dedup();
+    assert_eq!(all.len(), 8);
+}
+
+#[test]
+fn test_then() {
+    let grid = array![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+    for first in Orientation::all() {
+        for second in Orientation::all() {
+            assert_eq!(
+                second.apply(first.apply(grid.view())),
+                first.then(second).apply(grid.view()),
+                "{} then {}",
+                first,
+                second
+            );
+        }
+        assert_eq!(first.inverse().apply(first.apply(grid.view())), grid.view());
+    }
+}
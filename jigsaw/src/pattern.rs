@@ -0,0 +1,285 @@
+// Shapes to look for in a picture, such as 2020 day 20's sea monster. A
+// pattern is drawn in ASCII, with # for a pixel that must be set and a space
+// or . for a pixel that can be anything:
+//
+//                       #
+//     #    ##    ##    ###
+//      #  #  #  #  #  #
+//
+// The pattern can appear in the picture turned or flipped any way.
+
+use crate::orientation::Orientation;
+use ndarray::{s, Array2, ArrayView2};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum PatternError {
+    File(PathBuf, io::Error),
+    // Line and column, counting from 1
+    BadPixel {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    Empty,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::File(path, err) => write!(f, "{}: {}", path.display(), err),
+            PatternError::BadPixel {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "Line {}, column {}: bad pixel {:?}, expected #, . or space",
+                line, column, found
+            ),
+            PatternError::Empty => write!(f, "Pattern has no # pixels"),
+        }
+    }
+}
+
+impl Error for PatternError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PatternError::File(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+// Whether one match can share pixels with another. Without overlapping, a
+// match is skipped if it shares a pixel with one found before it, looking
+// row by row from the top left in each orientation in turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Counting {
+    Overlapping,
+    NonOverlapping,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    // How the pattern is turned to fit the picture
+    pub orientation: Orientation,
+    // The top left corner of the turned pattern in the picture
+    pub row: usize,
+    pub column: usize,
+}
+
+pub struct Matches {
+    pub matches: Vec<Match>,
+    // 1 for each pixel of the picture that is part of a match
+    pub mask: Array2<u8>,
+    // The picture with the pixels that are part of a match cleared
+    pub masked: Array2<u8>,
+}
+
+fn fits(pattern: &Array2<u8>, window: ArrayView2<u8>) -> bool {
+    pattern
+        .iter()
+        .zip(window.iter())
+        .all(|(&want, &pixel)| want == 0 || pixel != 0)
+}
+
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    // The distinct ways to turn the pattern, 1 for the pixels that must be
+    // set; a symmetrical pattern has fewer than eight
+    orientations: Vec<(Orientation, Array2<u8>)>,
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(text: &str) -> Result<Self, PatternError> {
+        let lines: Vec<_> = text.trim_end_matches('\n').lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max();
+        let mut pixels = Array2::zeros((lines.len(), width.unwrap_or(0)));
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                pixels[[y, x]] = match c {
+                    '#' => 1,
+                    ' ' | '.' => 0,
+                    _ => {
+                        return Err(PatternError::BadPixel {
+                            line: y + 1,
+                            column: x + 1,
+                            found: c,
+                        })
+                    }
+                };
+            }
+        }
+        Pattern::new(pixels)
+    }
+}
+
+impl Pattern {
+    // Pixels of 1 must be set, and 0 can be anything
+    pub fn new(pixels: Array2<u8>) -> Result<Self, PatternError> {
+        if !pixels.iter().any(|&pixel| pixel != 0) {
+            return Err(PatternError::Empty);
+        }
+        let mut orientations: Vec<(Orientation, Array2<u8>)> = vec![];
+        for orientation in Orientation::all() {
+            let turned = orientation.apply(pixels.view());
+            if orientations.iter().all(|(_, other)| *other != turned) {
+                orientations.push((orientation, turned.into_owned()));
+            }
+        }
+        Ok(Pattern { orientations })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, PatternError> {
+        let text = fs::read_to_string(path).map_err(|err| PatternError::File(path.into(), err))?;
+        text.parse()
+    }
+
+    // The number of pixels that must be set
+    pub fn pixel_count(&self) -> usize {
+        self.orientations[0]
+            .1
+            .iter()
+            .filter(|&&pixel| pixel != 0)
+            .count()
+    }
+
+    // The pixels of the picture covered by a match, as [row, column]
+    pub fn pixels(&self, found: &Match) -> impl Iterator<Item = [usize; 2]> + '_ {
+        let (_, turned) = self
+            .orientations
+            .iter()
+            .find(|(orientation, _)| *orientation == found.orientation)
+            .expect("match from another pattern");
+        let (row, column) = (found.row, found.column);
+        turned
+            .indexed_iter()
+            .filter(|(_, &pixel)| pixel != 0)
+            .map(move |((y, x), _)| [row + y, column + x])
+    }
+
+    // Every place the pattern appears in the picture, in any orientation
+    pub fn find(&self, picture: ArrayView2<u8>, counting: Counting) -> Matches {
+        let (rows, columns) = picture.dim();
+        let mut mask = Array2::zeros((rows, columns));
+        let mut matches = vec![];
+        for (orientation, pattern) in &self.orientations {
+            let (height, width) = pattern.dim();
+            if height > rows || width > columns {
+                continue;
+            }
+            for row in 0..=rows - height {
+                for column in 0..=columns - width {
+                    let window = picture.slice(s![row..row + height, column..column + width]);
+                    if !fits(pattern, window) {
+                        continue;
+                    }
+                    let found = Match {
+                        orientation: *orientation,
+                        row,
+                        column,
+                    };
+                    if counting == Counting::NonOverlapping
+                        && self.pixels(&found).any(|pixel| mask[pixel] != 0)
+                    {
+                        continue;
+                    }
+                    for pixel in self.pixels(&found) {
+                        mask[pixel] = 1;
+                    }
+                    matches.push(found);
+                }
+            }
+        }
+        let masked = &picture * &mask.map(|&covered| 1 - covered);
+        Matches {
+            matches,
+            mask,
+            masked,
+        }
+    }
+}
+
+#[cfg(test)]
+fn picture(rows: &[&str]) -> Array2<u8> {
+    let pixels: Vec<_> = rows
+        .iter()
+        .flat_map(|row| row.bytes().map(|b| (b == b'#') as u8))
+        .collect();
+    Array2::from_shape_vec((rows.len(), rows[0].len()), pixels).unwrap()
+}
+
+#[test]
+fn test_parse() {
+    let pattern: Pattern = "#.\n ##\n".parse().unwrap();
+    assert_eq!(pattern.pixel_count(), 3);
+    assert_eq!(pattern.orientations.len(), 8);
+    let square: Pattern = "##\n##".parse().unwrap();
+    assert_eq!(square.orientations.len(), 1);
+    assert!(matches!(
+        "#\n#x".parse::<Pattern>(),
+        Err(PatternError::BadPixel {
+            line: 2,
+            column: 2,
+            found: 'x'
+        })
+    ));
+    assert!(matches!(
+        "  \n..".parse::<Pattern>(),
+        Err(PatternError::Empty)
+    ));
+    assert!(matches!(
+        Pattern::read(Path::new("/nonexistent/pattern")),
+        Err(PatternError::File(_, _))
+    ));
+}
+
+#[test]
+fn test_orientations() {
+    let pattern: Pattern = "##\n#.".parse().unwrap();
+    assert_eq!(pattern.orientations.len(), 4);
+    let image = picture(&["....", ".##.", "..#.", "...."]);
+    let found = pattern.find(image.view(), Counting::Overlapping);
+    assert_eq!(found.matches.len(), 1);
+    let only = found.matches[0];
+    assert_eq!((only.row, only.column), (1, 1));
+    let mut pixels: Vec<_> = pattern.pixels(&only).collect();
+    pixels.sort_unstable();
+    assert_eq!(pixels, [[1, 1], [1, 2], [2, 2]]);
+    assert_eq!(found.mask, image);
+    assert!(found.masked.iter().all(|&pixel| pixel == 0));
+}
+
+#[test]
+fn test_overlapping() {
+    let pattern: Pattern = "###".parse().unwrap();
+    let image = picture(&["#####", ".....", "#.#.#"]);
+    let overlapping = pattern.find(image.view(), Counting::Overlapping);
+    assert_eq!(overlapping.matches.len(), 3);
+    let separate = pattern.find(image.view(), Counting::NonOverlapping);
+    assert_eq!(separate.matches.len(), 1);
+    assert_eq!(
+        (separate.matches[0].row, separate.matches[0].column),
+        (0, 0)
+    );
+    assert_eq!(separate.masked, picture(&["...##", ".....", "#.#.#"]));
+    assert_eq!(overlapping.masked, picture(&[".....", ".....", "#.#.#"]));
+}
+
+#[test]
+fn test_too_big() {
+    let pattern: Pattern = "####".parse().unwrap();
+    let image = picture(&["###", "###"]);
+    assert!(pattern
+        .find(image.view(), Counting::Overlapping)
+        .matches
+        .is_empty());
+}
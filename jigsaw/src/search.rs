@@ -0,0 +1,293 @@
+// Puts tiles together by backtracking, for when a border can match more than
+// one other tile and the greedy assembler can't tell which one goes next. The
+// grid is filled a row at a time; the tiles above and to the left of each
+// position leave only the tiles whose borders fit them to try there.
+//
+// The same picture turned or flipped is the same assembly, so each assembly
+// is counted once.
+
+use crate::assembly::{assemble, check_tiles, reversed, Assembly, JigsawError, Placement};
+use crate::orientation::Orientation;
+use crate::tile::{Border, Side, Tile};
+use ndarray::Array2;
+use std::collections::{HashMap, HashSet};
+
+// Index into the borders of a tile in each orientation, in the order of
+// Orientation::all()
+fn index_of(orientation: Orientation) -> usize {
+    4 * orientation.flipped as usize + (orientation.turns % 4) as usize
+}
+
+// Tile numbers and orientation indices, row by row, with the grid's shape
+type Key = ((usize, usize), Vec<(u64, usize)>);
+
+// The same for all the ways to turn and flip the whole picture, keeping the
+// grid no taller than it is wide
+fn canonical_key(placements: &Array2<Placement>) -> Key {
+    Orientation::all()
+        .map(|whole| {
+            let turned = whole.apply(placements.view());
+            let cells = turned
+                .iter()
+                .map(|placement| {
+                    let orientation = placement.orientation.then(whole);
+                    (placement.tile_id, index_of(orientation))
+                })
+                .collect();
+            (turned.dim(), cells)
+        })
+        .filter(|((rows, columns), _)| rows <= columns)
+        .min()
+        .unwrap()
+}
+
+struct Search<'a> {
+    tiles: &'a [Tile],
+    // borders[tile][orientation][side]
+    borders: Vec<Vec<[Border; 4]>>,
+    // Which tiles in which orientations have each top or left border
+    by_top: HashMap<Border, Vec<(usize, Orientation)>>,
+    by_left: HashMap<Border, Vec<(usize, Orientation)>>,
+    width: usize,
+    used: Vec<bool>,
+    placed: Vec<(usize, Orientation)>,
+    seen: HashSet<Key>,
+    found: Vec<Assembly>,
+    limit: usize,
+}
+
+impl<'a> Search<'a> {
+    fn new(tiles: &'a [Tile], limit: usize) -> Self {
+        let borders: Vec<Vec<_>> = tiles
+            .iter()
+            .map(|tile| Orientation::all().map(|o| tile.borders(o)).collect())
+            .collect();
+        let mut by_top: HashMap<_, Vec<_>> = HashMap::new();
+        let mut by_left: HashMap<_, Vec<_>> = HashMap::new();
+        for (ix, tile_borders) in borders.iter().enumerate() {
+            for orientation in Orientation::all() {
+                let sides = &tile_borders[index_of(orientation)];
+                let entry = (ix, orientation);
+                by_top
+                    .entry(sides[Side::Top as usize].clone())
+                    .or_default()
+                    .push(entry);
+                by_left
+                    .entry(sides[Side::Left as usize].clone())
+                    .or_default()
+                    .push(entry);
+            }
+        }
+        Search {
+            tiles,
+            borders,
+            by_top,
+            by_left,
+            width: 0,
+            used: vec![false; tiles.len()],
+            placed: Vec::with_capacity(tiles.len()),
+            seen: HashSet::new(),
+            found: vec![],
+            limit,
+        }
+    }
+
+    fn border(&self, (ix, orientation): (usize, Orientation), side: Side) -> &Border {
+        &self.borders[ix][index_of(orientation)][side as usize]
+    }
+
+    // The tiles that could go in the next position, fitting against the tiles
+    // already placed above and to the left of it
+    fn candidates(&self) -> Vec<(usize, Orientation)> {
+        let position = self.placed.len();
+        let left = (!position.is_multiple_of(self.width))
+            .then(|| reversed(self.border(self.placed[position - 1], Side::Right)));
+        let unused = |&&(ix, _): &&(usize, Orientation)| !self.used[ix];
+        if position >= self.width {
+            let top = reversed(self.border(self.placed[position - self.width], Side::Bottom));
+            let Some(fits) = self.by_top.get(&top) else {
+                return vec![];
+            };
+            fits.iter()
+                .filter(unused)
+                .filter(|&&candidate| {
+                    left.as_ref()
+                        .is_none_or(|left| self.border(candidate, Side::Left) == left)
+                })
+                .copied()
+                .collect()
+        } else if let Some(left) = left {
+            let Some(fits) = self.by_left.get(&left) else {
+                return vec![];
+            };
+            fits.iter().filter(unused).copied().collect()
+        } else {
+            (0..self.tiles.len())
+                .flat_map(|ix| Orientation::all().map(move |o| (ix, o)))
+                .collect()
+        }
+    }
+
+    fn record(&mut self) {
+        let placements: Vec<_> = self
+            .placed
+            .iter()
+            .map(|&(ix, orientation)| Placement {
+                tile_id: self.tiles[ix].id(),
+                orientation,
+            })
+            .collect();
+        let height = placements.len() / self.width;
+        let placements = Array2::from_shape_vec((height, self.width), placements).unwrap();
+        if self.seen.insert(canonical_key(&placements)) {
+            self.found.push(Assembly::new(self.tiles, placements));
+        }
+    }
+
+    // Returns false to stop once enough assemblies are found
+    fn fill(&mut self) -> bool {
+        if self.placed.len() == self.tiles.len() {
+            self.record();
+            return self.found.len() < self.limit;
+        }
+        for candidate in self.candidates() {
+            self.used[candidate.0] = true;
+            self.placed.push(candidate);
+            let go_on = self.fill();
+            self.placed.pop();
+            self.used[candidate.0] = false;
+            if !go_on {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// All the different ways to put the tiles together in a grid, up to `limit`
+// of them. Grids taller than they are wide are the same as wide ones turned.
+pub fn assemble_all(tiles: &[Tile], limit: usize) -> Result<Vec<Assembly>, JigsawError> {
+    check_tiles(tiles)?;
+    let mut search = Search::new(tiles, limit);
+    let count = tiles.len();
+    for height in (1..=count).take_while(|height| height * height <= count) {
+        if !count.is_multiple_of(height) || search.found.len() >= limit {
+            continue;
+        }
+        search.width = count / height;
+        search.fill();
+    }
+    Ok(search.found)
+}
+
+// Puts the tiles together greedily if that works, and otherwise searches for
+// the only way they fit. If there is more than one, the error says how many.
+pub fn assemble_unique(tiles: &[Tile]) -> Result<Assembly, JigsawError> {
+    match assemble(tiles) {
+        Err(JigsawError::Ambiguous(_)) => {
+            let mut found = assemble_all(tiles, usize::MAX)?;
+            match found.len() {
+                0 => Err(JigsawError::NoAssembly),
+                1 => Ok(found.remove(0)),
+                count => Err(JigsawError::Multiple(count)),
+            }
+        }
+        result => result,
+    }
+}
+
+// Cuts a random picture into a grid of tiles of side 5, with neighbouring
+// tiles sharing a line of pixels, then turns each tile some way. `edit` can
+// change the picture before it is cut up.
+#[cfg(test)]
+fn cut_up(rows: usize, columns: usize, edit: impl FnOnce(&mut Array2<u8>)) -> Vec<Tile> {
+    let (side, step) = (5, 4);
+    let mut picture = Array2::from_shape_fn((rows * step + 1, columns * step + 1), |(y, x)| {
+        let mut hash = ((y as u64) << 32 | x as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((hash ^ (hash >> 31)) & 1) as u8
+    });
+    edit(&mut picture);
+    let mut tiles = vec![];
+    for row in 0..rows {
+        for column in 0..columns {
+            let (y, x) = (row * step, column * step);
+            let pixels = picture.slice(ndarray::s![y..y + side, x..x + side]);
+            let turned = Orientation {
+                flipped: column == 1,
+                turns: (row + 2 * column) as u8,
+            };
+            let id = (10 * row + column + 1) as u64;
+            tiles.push(Tile::new(id, turned.apply(pixels).into_owned()).unwrap());
+        }
+    }
+    tiles
+}
+
+#[test]
+fn test_example() {
+    let tiles = crate::assembly::read_tiles(crate::assembly::EXAMPLE);
+    let found = assemble_all(&tiles, usize::MAX).unwrap();
+    assert_eq!(found.len(), 1);
+    let greedy = assemble(&tiles).unwrap();
+    assert_eq!(
+        canonical_key(found[0].placements()),
+        canonical_key(greedy.placements())
+    );
+    assert_eq!(assemble_unique(&tiles).unwrap().corners(), greedy.corners());
+    assert!(assemble_all(&tiles[1..], usize::MAX).unwrap().is_empty());
+}
+
+#[test]
+fn test_rectangle() {
+    let tiles = cut_up(2, 3, |_| {});
+    let found = assemble_all(&tiles, usize::MAX).unwrap();
+    assert_eq!(found.len(), 1);
+    let placements = found[0].placements();
+    assert_eq!(placements.dim(), (2, 3));
+    assert_eq!(placements[[0, 1]].tile_id % 10, 2);
+    assert_eq!(placements[[1, 1]].tile_id % 10, 2);
+}
+
+// Tiles 1 and 12, in opposite corners of a 2x2 grid, have the same pixels,
+// so their borders each match two other tiles and they can swap places
+#[test]
+fn test_ambiguous() {
+    let tiles = cut_up(2, 2, |picture| {
+        let corner = picture[[0, 0]];
+        picture[[4, 4]] = corner;
+        picture[[8, 8]] = corner;
+        let first = picture.slice(ndarray::s![0..5, 0..5]).to_owned();
+        picture.slice_mut(ndarray::s![4..9, 4..9]).assign(&first);
+    });
+    assert_eq!(assemble(&tiles).err(), Some(JigsawError::Ambiguous(1)));
+    let found = assemble_all(&tiles, usize::MAX).unwrap();
+    assert_eq!(found.len(), 2);
+    for assembly in &found {
+        let mut corners = assembly.corners();
+        corners.sort_unstable();
+        assert_eq!(corners, [1, 2, 11, 12]);
+    }
+    let image = found[1].image();
+    assert!(Orientation::all().any(|o| o.apply(image.view()) == found[0].image()));
+    assert_eq!(assemble_all(&tiles, 1).unwrap().len(), 1);
+    let err = assemble_unique(&tiles).err().unwrap();
+    assert_eq!(err, JigsawError::Multiple(2));
+    assert_eq!(
+        err.to_string(),
+        "The tiles fit together in 2 different ways"
+    );
+}
+
+// Blank tiles fit together any way at all: 2 orders times 8 orientations for
+// each of the 2 tiles, with 4 ways to turn and flip a 1x2 grid that keep it
+// the same shape
+#[test]
+fn test_blank() {
+    let tiles: Vec<_> = (1..=2)
+        .map(|id| Tile::new(id, Array2::zeros((3, 3))).unwrap())
+        .collect();
+    let count = assemble_all(&tiles, usize::MAX).unwrap().len();
+    assert_eq!(count, 2 * 64 / 4);
+}
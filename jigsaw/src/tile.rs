@@ -0,0 +1,201 @@
+use crate::assembly::JigsawError;
+use crate::orientation::Orientation;
+use ndarray::{s, Array2, ArrayView2};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    // Clockwise from the top
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    pub fn opposite(self) -> Self {
+        Side::ALL[(self as usize + 2) % 4]
+    }
+}
+
+// A border's pixels, read clockwise around the tile. Two tiles fit together
+// along a border when one reads the same as the other backwards.
+pub type Border = Vec<u8>;
+
+// The same for a border and its reverse, to find borders that could match
+pub(crate) fn canonical(border: &[u8]) -> Border {
+    let reversed: Border = border.iter().rev().copied().collect();
+    reversed.min(border.to_vec())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tile {
+    id: u64,
+    pixels: Array2<u8>,
+}
+
+impl Tile {
+    // The pixels must be a square of side at least 3, so that there is
+    // something inside the borders
+    pub fn new(id: u64, pixels: Array2<u8>) -> Result<Self, JigsawError> {
+        let (rows, columns) = pixels.dim();
+        if rows != columns || rows < 3 {
+            return Err(JigsawError::BadShape { id, rows, columns });
+        }
+        Ok(Tile { id, pixels })
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    // The length of a side, borders included
+    pub fn size(&self) -> usize {
+        self.pixels.nrows()
+    }
+
+    pub fn pixels(&self, orientation: Orientation) -> ArrayView2<'_, u8> {
+        orientation.apply(self.pixels.view())
+    }
+
+    // The pixels inside the borders
+    pub fn image(&self, orientation: Orientation) -> ArrayView2<'_, u8> {
+        let inside = 1..self.size() - 1;
+        self.pixels(orientation)
+            .slice_move(s![inside.clone(), inside])
+    }
+
+    pub fn border(&self, orientation: Orientation, side: Side) -> Border {
+        let pixels = self.pixels(orientation);
+        let edge = match side {
+            Side::Top => pixels.slice_move(s![0, ..]),
+            Side::Right => pixels.slice_move(s![.., -1]),
+            Side::Bottom => pixels.slice_move(s![-1, ..;-1]),
+            Side::Left => pixels.slice_move(s![..;-1, 0]),
+        };
+        edge.to_vec()
+    }
+
+    pub fn borders(&self, orientation: Orientation) -> [Border; 4] {
+        Side::ALL.map(|side| self.border(orientation, side))
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+static TEST_TILE: [&str; 8] = [
+    ".#......",
+    "....###.",
+    "......##",
+    "........",
+    "#.#.....",
+    "..#.....",
+    "........",
+    ".....##.",
+];
+
+#[cfg(test)]
+fn test_tile() -> Tile {
+    let pixels: Vec<_> = TEST_TILE
+        .iter()
+        .flat_map(|row| row.bytes().map(|b| (b == b'#') as u8))
+        .collect();
+    Tile::new(1, Array2::from_shape_vec((8, 8), pixels).unwrap()).unwrap()
+}
+
+// The borders as bits, counting from the least significant bit
+#[cfg(test)]
+fn bits(tile: &Tile, orientation: Orientation) -> [u64; 4] {
+    tile.borders(orientation).map(|border| {
+        border
+            .iter()
+            .enumerate()
+            .map(|(ix, &pixel)| (pixel as u64) << ix)
+            .sum()
+    })
+}
+
+#[test]
+fn tile_read() {
+    let tile = test_tile();
+    assert_eq!(tile.id(), 1);
+    assert_eq!(tile.size(), 8);
+    assert_eq!(bits(&tile, Orientation::IDENTITY), [2, 4, 6, 8]);
+    assert_eq!(
+        tile.image(Orientation::IDENTITY),
+        ndarray::arr2(&[
+            [0, 0, 0, 1, 1, 1],
+            [0, 0, 0, 0, 0, 1],
+            [0, 0, 0, 0, 0, 0],
+            [0, 1, 0, 0, 0, 0],
+            [0, 1, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0],
+        ])
+    );
+}
+
+#[test]
+fn tile_rotate() {
+    let tile = test_tile();
+    let orientation = Orientation {
+        flipped: false,
+        turns: 1,
+    };
+    assert_eq!(bits(&tile, orientation), [4, 6, 8, 2]);
+    assert_eq!(
+        tile.image(orientation),
+        ndarray::arr2(&[
+            [1, 1, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 1, 1, 0],
+            [0, 0, 0, 0, 0, 0],
+        ])
+    );
+}
+
+#[test]
+fn tile_fliplr() {
+    let tile = test_tile();
+    let orientation = Orientation {
+        flipped: true,
+        turns: 0,
+    };
+    assert_eq!(bits(&tile, orientation), [64, 16, 96, 32]);
+    assert_eq!(
+        tile.image(orientation),
+        ndarray::arr2(&[
+            [1, 1, 1, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 1, 0],
+            [0, 0, 0, 0, 0, 0],
+        ])
+    );
+}
+
+#[test]
+fn tile_bad_shape() {
+    let bad = |rows, columns| Tile::new(7, Array2::zeros((rows, columns))).err();
+    assert_eq!(
+        bad(4, 5),
+        Some(JigsawError::BadShape {
+            id: 7,
+            rows: 4,
+            columns: 5
+        })
+    );
+    assert!(bad(2, 2).is_some());
+    assert!(bad(40, 40).is_none());
+}
+
+#[test]
+fn direction_opposite() {
+    assert_eq!(Side::Left.opposite(), Side::Right);
+    assert_eq!(Side::Right.opposite(), Side::Left);
+    assert_eq!(Side::Top.opposite(), Side::Bottom);
+    assert_eq!(Side::Bottom.opposite(), Side::Top);
+}
@@ -0,0 +1,53 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use itertools::Itertools;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum ExpenseError {
+    BadEntry(ParseIntError),
+}
+
+impl fmt::Display for ExpenseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpenseError::BadEntry(err) => write!(f, "Bad expense entry: {}", err),
+        }
+    }
+}
+
+impl Error for ExpenseError {}
+
+pub struct Puzzle {
+    entries: Vec<i32>,
+}
+
+impl Solution for Puzzle {
+    type Error = ExpenseError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<ExpenseError>> {
+        let entries = parse_lines(input, |line| {
+            line.parse()
+                .map_err(|err| ParseError::at_column(1, ExpenseError::BadEntry(err)))
+        })?;
+        Ok(Puzzle { entries })
+    }
+
+    fn part1(&self) -> Answer {
+        find_pair(&self.entries).into()
+    }
+
+    fn part2(&self) -> Answer {
+        puzzle1_2::find_triple(&self.entries).into()
+    }
+}
+
+fn find_pair(entries: &[i32]) -> Option<i32> {
+    entries
+        .iter()
+        .tuple_combinations()
+        .find(|(first, second)| *first + *second == 2020)
+        .map(|(first, second)| first * second)
+}
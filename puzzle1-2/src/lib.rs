@@ -0,0 +1,9 @@
+use itertools::Itertools;
+
+pub fn find_triple(entries: &[i32]) -> Option<i32> {
+    entries
+        .iter()
+        .tuple_combinations()
+        .find(|(first, second, third)| *first + *second + *third == 2020)
+        .map(|(first, second, third)| first * second * third)
+}
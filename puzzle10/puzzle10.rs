@@ -1,39 +1,71 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use itertools::Itertools;
-use std::env;
-use std::fs;
-use std::io::{self, BufRead};
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
 
-fn main() -> Result<(), io::Error> {
-    let file = fs::File::open("input")?;
-    let mut adapters: Vec<u8> = read_lines(file).map(|s| s.parse().unwrap()).collect();
-    adapters.push(0); // add charging outlet
-    adapters.sort_unstable();
-    adapters.push(adapters.last().unwrap() + 3); // add built-in adapter
+#[derive(Debug, PartialEq)]
+pub enum AdapterError {
+    BadJoltage(ParseIntError),
+}
 
-    let differences = adapters.iter().tuple_windows().map(|(j1, j2)| j2 - j1);
+impl fmt::Display for AdapterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdapterError::BadJoltage(err) => write!(f, "Bad joltage: {}", err),
+        }
+    }
+}
 
-    if is_part2() {
-        let groups = differences.group_by(|d| *d);
-        let total: u64 = groups
-            .into_iter()
-            .filter(|(key, _)| *key == 1)
-            .map(|(_, group)| possible_configurations(group.count()))
-            .product();
-        println!("{}", total);
-    } else {
+impl Error for AdapterError {}
+
+pub struct Puzzle {
+    adapters: Vec<u8>,
+}
+
+impl Puzzle {
+    fn differences(&self) -> impl Iterator<Item = u8> + '_ {
+        self.adapters.iter().tuple_windows().map(|(j1, j2)| j2 - j1)
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = AdapterError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<AdapterError>> {
+        let mut adapters = parse_lines(input, |line| {
+            line.parse::<u8>()
+                .map_err(|err| ParseError::at_column(1, AdapterError::BadJoltage(err)))
+        })?;
+        adapters.push(0); // add charging outlet
+        adapters.sort_unstable();
+        adapters.push(adapters.last().unwrap() + 3); // add built-in adapter
+        Ok(Puzzle { adapters })
+    }
+
+    fn part1(&self) -> Answer {
         let mut ones = 0;
         let mut threes = 0;
-        for difference in differences {
+        for difference in self.differences() {
             match difference {
                 1 => ones += 1,
                 3 => threes += 1,
                 _ => (),
             }
         }
-        println!("{}", ones * threes);
+        (ones * threes).into()
     }
 
-    Ok(())
+    fn part2(&self) -> Answer {
+        let groups = self.differences().group_by(|d| *d);
+        let total: u64 = groups
+            .into_iter()
+            .filter(|(key, _)| *key == 1)
+            .map(|(_, group)| possible_configurations(group.count()))
+            .product();
+        total.into()
+    }
 }
 
 fn possible_configurations(run_length: usize) -> u64 {
@@ -46,11 +78,3 @@ fn possible_configurations(run_length: usize) -> u64 {
         n => 2 * possible_configurations(n - 1) - possible_configurations(n - 4),
     }
 }
-
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
-
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
-}
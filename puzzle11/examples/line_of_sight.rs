@@ -0,0 +1,123 @@
+// Times part 2 on the real input two ways: with the old seating loop, which
+// looks outward from every seat for the nearest seat in each direction in
+// every round, and with the automaton, which works that out once and keeps it
+// as a graph. Run with
+//
+//     cargo run --release -p puzzle11 --example line_of_sight [iterations]
+
+use automaton::engine::Automaton;
+use automaton::neighbourhood::Neighbourhood;
+use automaton::rule::Rule;
+use common::bench::Stats;
+use common::input::{self, Source};
+use ndarray::{Array2, ArrayD};
+use std::env;
+use std::error::Error;
+use std::hint::black_box;
+use std::time::Instant;
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn nearest_seat(
+    seats: &Array2<u8>,
+    y: usize,
+    x: usize,
+    (dy, dx): (isize, isize),
+) -> Option<[usize; 2]> {
+    let (mut y, mut x) = (y, x);
+    loop {
+        y = y.checked_add_signed(dy).filter(|&y| y < seats.nrows())?;
+        x = x.checked_add_signed(dx).filter(|&x| x < seats.ncols())?;
+        if seats[[y, x]] != 0 {
+            return Some([y, x]);
+        }
+    }
+}
+
+fn scanning(seats: &Array2<u8>) -> usize {
+    let mut occupied = Array2::<u8>::zeros(seats.raw_dim());
+    loop {
+        let mut next = occupied.clone();
+        for ((y, x), &seat) in seats.indexed_iter() {
+            if seat == 0 {
+                continue;
+            }
+            let neighbours: u8 = DIRECTIONS
+                .iter()
+                .filter_map(|&direction| nearest_seat(seats, y, x, direction))
+                .map(|seen| occupied[seen])
+                .sum();
+            match (occupied[[y, x]], neighbours) {
+                (0, 0) => next[[y, x]] = 1,
+                (1, 5..) => next[[y, x]] = 0,
+                _ => {}
+            }
+        }
+        if next == occupied {
+            return occupied.iter().filter(|&&seat| seat != 0).count();
+        }
+        occupied = next;
+    }
+}
+
+fn graph(seats: &Array2<u8>) -> usize {
+    let rule = Rule::new([0], 0..=4);
+    let start = ArrayD::zeros(seats.shape());
+    let mut automaton = Automaton::new(start, Neighbourhood::LineOfSight, &rule)
+        .with_mask(seats.clone().into_dyn());
+    while automaton.step() {}
+    automaton.population()
+}
+
+fn time(name: &str, iterations: usize, seats: &Array2<u8>, run: fn(&Array2<u8>) -> usize) -> usize {
+    let mut samples = vec![];
+    let mut answer = 0;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        answer = black_box(run(black_box(seats)));
+        samples.push(start.elapsed());
+    }
+    if let Some(stats) = Stats::from_samples(&mut samples) {
+        println!(
+            "{:<8} {:>12} {:>12} {:>12}",
+            name,
+            format!("{:.3?}", stats.min),
+            format!("{:.3?}", stats.median),
+            format!("{:.3?}", stats.max),
+        );
+    }
+    answer
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let iterations = match env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 10,
+    };
+    let input = input::read(&Source::Puzzle {
+        year: 2020,
+        day: 11,
+    })?;
+    let lines: Vec<_> = input.lines().collect();
+    let width = lines.first().map_or(0, |line| line.len());
+    let seats = Array2::from_shape_fn((lines.len(), width), |(y, x)| {
+        (lines[y].as_bytes().get(x) == Some(&b'L')) as u8
+    });
+
+    println!("{:<8} {:>12} {:>12} {:>12}", "", "min", "median", "max");
+    let scanned = time("scanning", iterations, &seats, scanning);
+    let graphed = time("graph", iterations, &seats, graph);
+    if scanned != graphed {
+        return Err(format!("scanning gave {}, but the graph gave {}", scanned, graphed).into());
+    }
+    Ok(())
+}
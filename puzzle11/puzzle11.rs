@@ -1,142 +1,162 @@
-use itertools::Itertools;
-use ndarray::{s, Array2};
-use std::env;
-use std::fs;
-use std::io::{self, BufRead};
-
-enum Tile {
-    FLOOR = 0,
-    SEAT = 1,
-}
-
-fn main() -> Result<(), io::Error> {
-    let file = fs::File::open("input")?;
-    let tiles = read_board(file);
-    let mut seats = Array2::<i8>::zeros(tiles.raw_dim());
+use automaton::engine::Automaton;
+use automaton::history::{self, Summary};
+use automaton::neighbourhood::Neighbourhood;
+use automaton::render::{Draw, Frame, Layout, Palette, Renderer};
+use automaton::rule::Rule;
+use common::error::ParseError;
+use common::solution::{Answer, Solution};
+use ndarray::{Array2, ArrayD};
+use std::error::Error;
+use std::fmt;
 
-    let occupied = loop {
-        let neighbours = if is_part2() {
-            calc_los_neighbours(&seats, &tiles)
-        } else {
-            calc_neighbours(&seats)
-        };
-        let arrivals = (&neighbours + &seats).mapv(|count| (count == 0) as i8);
-        let departures = &neighbours.mapv(if is_part2() {
-            |count| (count >= 5) as i8
-        } else {
-            |count| (count >= 4) as i8
-        }) * &seats;
-        let new_seats = (&seats + &arrivals - &departures) * &tiles;
-        if seats == new_seats {
-            break seats.mapv(|e| e as i32).sum();
-        }
-        seats = new_seats;
-    };
-
-    println!("Answered: {}", occupied);
-    Ok(())
+#[derive(Debug, PartialEq)]
+pub enum LayoutError {
+    Empty,
+    BadTile(char),
+    RaggedRow { expected: usize, got: usize },
 }
 
-static DIRECTIONS: &[(isize, isize)] = &[
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
-
-fn calc_los_neighbours(seats: &Array2<i8>, tiles: &Array2<i8>) -> Array2<i8> {
-    let mut neighbours = Array2::<i8>::zeros(seats.raw_dim());
-    for x in 0..seats.nrows() {
-        for y in 0..seats.ncols() {
-            for dir in DIRECTIONS {
-                if let Some(seat) = nearest_seat(tiles, x, y, dir) {
-                    neighbours[[x, y]] += seats[seat];
-                }
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Empty => write!(f, "Seat layout is empty"),
+            LayoutError::BadTile(c) => write!(f, "Bad tile {:?}, expected L or .", c),
+            LayoutError::RaggedRow { expected, got } => {
+                write!(f, "Row is {} wide, but the first row is {}", got, expected)
             }
         }
     }
-    neighbours
 }
 
-fn nearest_seat(
-    tiles: &Array2<i8>,
-    x: usize,
-    y: usize,
-    &(dx, dy): &(isize, isize),
-) -> Option<(usize, usize)> {
-    let mut nx = x;
-    let mut ny = y;
-    let xmax = tiles.nrows() - 1;
-    let ymax = tiles.ncols() - 1;
-    loop {
-        if (dx == -1 && nx == 0)
-            || (dx == 1 && nx >= xmax)
-            || (dy == -1 && ny == 0)
-            || (dy == 1 && ny >= ymax)
-        {
-            break None; // no seat in line of sight in this direction
-        }
-        nx = incdec(nx, dx);
-        ny = incdec(ny, dy);
-        if tiles[[nx, ny]] == Tile::SEAT as i8 {
-            break Some((nx, ny));
-        }
+impl Error for LayoutError {}
+
+pub struct Puzzle {
+    tiles: Array2<u8>,
+}
+
+impl Solution for Puzzle {
+    type Error = LayoutError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<LayoutError>> {
+        Ok(Puzzle {
+            tiles: read_board(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.simulate(false).stable_population().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.simulate(true).stable_population().into()
     }
 }
 
-fn incdec(val: usize, delta: isize) -> usize {
-    match delta {
-        1 => val + 1,
-        -1 => val - 1,
-        _ => val,
+// Far more rounds than any layout in the puzzle takes to settle
+const MAX_ROUNDS: usize = 1000;
+
+impl Puzzle {
+    // Runs the seating rules until the seats settle or start repeating, with
+    // the number of occupied seats after each round
+    pub fn simulate(&self, is_part2: bool) -> Summary {
+        let mut automaton = seating(&self.tiles, is_part2);
+        history::run(&mut automaton, MAX_ROUNDS)
     }
 }
 
-fn calc_neighbours(seats: &Array2<i8>) -> Array2<i8> {
-    let shape = seats.shape();
-    let width = shape[0] as isize;
-    let height = shape[1] as isize;
-    let mut neighbours = Array2::<i8>::zeros(seats.raw_dim());
-    // Add slices of the occupied seats shifted one space in each direction
-    for (xstart, ystart) in (-1..=1).cartesian_product(-1..=1) {
-        if xstart == 0 && ystart == 0 {
-            continue;
+// Seats are the automaton's mask, so the floor never gets occupied. An empty
+// seat fills up if it has no occupied neighbours, and an occupied one empties
+// if it has too many.
+fn seating(tiles: &Array2<u8>, is_part2: bool) -> Automaton {
+    let (neighbourhood, tolerance) = if is_part2 {
+        (Neighbourhood::LineOfSight, 4)
+    } else {
+        (Neighbourhood::Moore, 3)
+    };
+    let rule = Rule::new([0], 0..=tolerance);
+    let seats = ArrayD::zeros(tiles.shape());
+    Automaton::new(seats, neighbourhood, &rule).with_mask(tiles.clone().into_dyn())
+}
+
+// Floor, empty seats and occupied seats, one frame per round
+impl Draw for Puzzle {
+    fn renderer(&self) -> Renderer {
+        let palette = vec![
+            ('.', [0x30, 0x30, 0x30]),
+            ('L', [0x80, 0x80, 0xa0]),
+            ('#', [0xf0, 0xc0, 0x40]),
+        ];
+        Renderer::new(Layout::Square).with_palette(Palette::new(palette))
+    }
+
+    fn frames(&self, is_part2: bool) -> Vec<Frame> {
+        let mut automaton = seating(&self.tiles, is_part2);
+        let frame = |automaton: &Automaton| {
+            let mut frame = Frame::of(automaton);
+            frame.cells += &self.tiles;
+            frame
+        };
+        let mut frames = vec![frame(&automaton)];
+        while automaton.step() && frames.len() <= MAX_ROUNDS {
+            frames.push(frame(&automaton));
         }
-        let xdest = xstart.max(0)..(width + xstart).min(width);
-        let ydest = ystart.max(0)..(height + ystart).min(height);
-        let xsource = (-xstart).max(0)..(width - xstart).min(width);
-        let ysource = (-ystart).max(0)..(height - ystart).min(height);
-        let mut slice = neighbours.slice_mut(s![xdest, ydest]);
-        slice += &seats.slice(s![xsource, ysource]);
+        frames
     }
-    neighbours
 }
 
-fn read_board(file: fs::File) -> Array2<i8> {
-    let lines: Vec<String> = read_lines(file).collect();
+fn read_board(input: &str) -> Result<Array2<u8>, ParseError<LayoutError>> {
+    let lines: Vec<&str> = input.lines().collect();
     let height = lines.len();
-    let width = lines[0].len();
-    let mut cells = Array2::zeros((width, height));
+    let width = match lines.first() {
+        Some(line) if !line.is_empty() => line.len(),
+        _ => return Err(ParseError::new(1, 1, LayoutError::Empty)),
+    };
+    let mut cells = Array2::zeros((height, width));
     for (y, line) in lines.iter().enumerate() {
-        for (x, tile) in line.bytes().enumerate() {
-            cells[[x, y]] = match tile {
-                b'L' => Tile::SEAT as i8,
-                b'.' => Tile::FLOOR as i8,
-                _ => panic!("Bad tile '{}'", tile),
+        if line.len() != width {
+            let kind = LayoutError::RaggedRow {
+                expected: width,
+                got: line.len(),
+            };
+            return Err(ParseError::new(y + 1, 1, kind));
+        }
+        for (x, tile) in line.chars().enumerate() {
+            cells[[y, x]] = match tile {
+                'L' => 1,
+                '.' => 0,
+                _ => return Err(ParseError::new(y + 1, x + 1, LayoutError::BadTile(tile))),
             };
         }
     }
-    cells
+    Ok(cells)
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
+#[test]
+fn test_example() {
+    let input = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL";
+    let puzzle = Puzzle::parse(input).unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(37));
+    assert_eq!(puzzle.part2(), Answer::from(26));
+
+    let summary = puzzle.simulate(false);
+    assert_eq!(summary.populations, [0, 71, 20, 51, 30, 37]);
+    assert_eq!(summary.stable_from(), Some(5));
+    let summary = puzzle.simulate(true);
+    assert_eq!(summary.populations, [0, 71, 7, 53, 18, 31, 26]);
+    assert_eq!(summary.stable_from(), Some(6));
 
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
+    let frames = puzzle.frames(false);
+    assert_eq!(frames.len(), 6);
+    let art = puzzle.renderer().ascii(&frames[2]);
+    assert_eq!(art.lines().next(), Some("#.LL.L#.##"));
 }
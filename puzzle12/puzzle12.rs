@@ -1,6 +1,35 @@
-use std::env;
-use std::fs;
-use std::io::{self, BufRead};
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum NavigationError {
+    BadAction(String),
+    BadValue(ParseIntError),
+    BadAngle(i32),
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationError::BadAction(action) => {
+                write!(
+                    f,
+                    "Bad action {:?}, expected N, S, E, W, L, R, or F",
+                    action
+                )
+            }
+            NavigationError::BadValue(err) => write!(f, "Bad value: {}", err),
+            NavigationError::BadAngle(angle) => {
+                write!(f, "Bad angle {}, expected 90, 180, or 270", angle)
+            }
+        }
+    }
+}
+
+impl Error for NavigationError {}
 
 #[derive(Debug)]
 enum Direction {
@@ -14,19 +43,34 @@ enum Direction {
 }
 
 impl Direction {
-    fn from_string(line: &str) -> Self {
+    fn from_string(line: &str) -> Result<Self, ParseError<NavigationError>> {
         use Direction::*;
-        let parameter = &line[1..];
-        match line.chars().next().unwrap() {
-            'N' => North(parameter.parse().unwrap()),
-            'S' => South(parameter.parse().unwrap()),
-            'E' => East(parameter.parse().unwrap()),
-            'W' => West(parameter.parse().unwrap()),
-            'L' => Left(parameter.parse().unwrap()),
-            'R' => Right(parameter.parse().unwrap()),
-            'F' => Forward(parameter.parse().unwrap()),
-            _ => panic!("Bad instruction {}", line),
+        let split = line.char_indices().nth(1).map_or(line.len(), |(ix, _)| ix);
+        let (action, parameter) = line.split_at(split);
+        let value = parameter
+            .parse()
+            .map_err(|err| ParseError::at_column(split + 1, NavigationError::BadValue(err)))?;
+        if matches!(action, "L" | "R") && !matches!(value, 90 | 180 | 270) {
+            return Err(ParseError::at_column(
+                split + 1,
+                NavigationError::BadAngle(value),
+            ));
         }
+        Ok(match action {
+            "N" => North(value),
+            "S" => South(value),
+            "E" => East(value),
+            "W" => West(value),
+            "L" => Left(value),
+            "R" => Right(value),
+            "F" => Forward(value),
+            _ => {
+                return Err(ParseError::at_column(
+                    1,
+                    NavigationError::BadAction(action.into()),
+                ))
+            }
+        })
     }
 }
 
@@ -109,26 +153,37 @@ impl Ship {
     }
 }
 
-fn main() -> Result<(), io::Error> {
-    let file = fs::File::open("input")?;
-    let mut ship = Ship::new();
-    read_lines(file)
-        .map(|s| Direction::from_string(&s))
-        .for_each(|dir| {
-            if is_part2() {
-                ship.move_waypoint(&dir)
+pub struct Puzzle {
+    directions: Vec<Direction>,
+}
+
+impl Puzzle {
+    fn sail(&self, is_part2: bool) -> i32 {
+        let mut ship = Ship::new();
+        self.directions.iter().for_each(|dir| {
+            if is_part2 {
+                ship.move_waypoint(dir)
             } else {
-                ship.go(&dir)
+                ship.go(dir)
             }
         });
-    println!("{}", ship.manhattan_distance());
-    Ok(())
+        ship.manhattan_distance()
+    }
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
+impl Solution for Puzzle {
+    type Error = NavigationError;
 
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
+    fn parse(input: &str) -> Result<Self, ParseError<NavigationError>> {
+        let directions = parse_lines(input, Direction::from_string)?;
+        Ok(Puzzle { directions })
+    }
+
+    fn part1(&self) -> Answer {
+        self.sail(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.sail(true).into()
+    }
 }
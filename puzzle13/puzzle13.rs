@@ -1,19 +1,82 @@
-use std::env;
+use common::error::{column_of, ParseError};
+use common::solution::{Answer, Solution};
 use std::error::Error;
-use std::fs;
-use std::io::{self, BufRead};
+use std::fmt;
+use std::num::ParseIntError;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let file = fs::File::open("input")?;
-    let mut lines = read_lines(file);
-    let arrival: u64 = lines.next().unwrap().parse()?;
+#[derive(Debug, PartialEq)]
+pub enum ScheduleError {
+    MissingArrival,
+    BadArrival(ParseIntError),
+    MissingTable,
+    BadBus(String),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::MissingArrival => write!(f, "Missing arrival time"),
+            ScheduleError::BadArrival(err) => write!(f, "Bad arrival time: {}", err),
+            ScheduleError::MissingTable => write!(f, "Missing bus table"),
+            ScheduleError::BadBus(bus) => {
+                write!(f, "Bad bus {:?}, expected a positive number or x", bus)
+            }
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+pub struct Puzzle {
+    arrival: u64,
+    table: String,
+}
+
+impl Solution for Puzzle {
+    type Error = ScheduleError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<ScheduleError>> {
+        let mut lines = input.lines();
+        let arrival = lines
+            .next()
+            .ok_or_else(|| ParseError::new(1, 1, ScheduleError::MissingArrival))?
+            .parse()
+            .map_err(|err| ParseError::new(1, 1, ScheduleError::BadArrival(err)))?;
+        let table = lines
+            .next()
+            .ok_or_else(|| ParseError::new(2, 1, ScheduleError::MissingTable))?;
+        for bus in table.split(',') {
+            if bus != "x" && !matches!(bus.parse::<u64>(), Ok(n) if n > 0) {
+                let column = column_of(table, bus);
+                return Err(ParseError::new(
+                    2,
+                    column,
+                    ScheduleError::BadBus(bus.into()),
+                ));
+            }
+        }
+        Ok(Puzzle {
+            arrival,
+            table: table.to_string(),
+        })
+    }
 
-    let table = lines.next().unwrap();
-    let entries = table.split(',');
+    fn part1(&self) -> Answer {
+        let arrival = self.arrival;
+        self.table
+            .split(',')
+            .filter_map(|s| s.parse::<u64>().ok()) // available bus lines
+            .map(|interval| (interval, interval - arrival % interval)) // (bus_number, wait time)
+            .min_by_key(|(_, wait_time)| *wait_time)
+            .map(|(bus_number, wait_time)| bus_number * wait_time)
+            .into()
+    }
 
-    if is_part2() {
+    fn part2(&self) -> Answer {
         let mut t: u64 = 100000000000000;
-        let mut constraints: Vec<(usize, u64)> = entries
+        let mut constraints: Vec<(usize, u64)> = self
+            .table
+            .split(',')
             .enumerate()
             .filter_map(|(ix, s)| s.parse::<u64>().map(|n| (ix, n)).ok()) // (index, bus_number)
             .collect();
@@ -25,7 +88,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         loop {
             match constraints
                 .iter()
-                .position(|(delay, bus)| (t + *delay as u64) % *bus != 0)
+                .position(|(delay, bus)| !(t + *delay as u64).is_multiple_of(*bus))
             {
                 None => break,
                 Some(ix) => {
@@ -38,23 +101,6 @@ fn main() -> Result<(), Box<dyn Error>> {
             t += step;
         }
 
-        println!("{}", t);
-    } else {
-        let (bus_number, wait_time) = entries
-            .filter_map(|s| s.parse::<u64>().ok()) // available bus lines
-            .map(|interval| (interval, interval - arrival % interval)) // (bus_number, wait time)
-            .min_by_key(|(_, wait_time)| *wait_time)
-            .unwrap();
-
-        println!("{}", bus_number * wait_time);
+        t.into()
     }
-    Ok(())
-}
-
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
-
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
 }
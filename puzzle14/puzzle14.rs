@@ -1,54 +1,124 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use std::collections::HashMap;
-use std::convert::TryInto;
-use std::env;
 use std::error::Error;
-use std::fs;
-use std::io::{self, BufRead};
-use std::num;
+use std::fmt;
 
 #[macro_use]
 extern crate scan_fmt;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut memory = HashMap::new();
-    let mut or_mask: u64 = 0;
-    let mut and_mask: u64 = u64::MAX;
-    let mut float_mask: u64 = 0;
+enum Line {
+    Mask(u64, u64, u64), // (or_mask, and_mask, float_mask)
+    Write(u64, u64),     // (addr, value)
+}
 
-    let file = fs::File::open("input")?;
-    for line in read_lines(file) {
-        if line.starts_with("mask") {
-            let (new_or_mask, new_and_mask, new_float_mask) = parse_mask(&line[7..])?;
-            or_mask = new_or_mask;
-            and_mask = new_and_mask;
-            float_mask = new_float_mask;
-            continue;
+const MASK_BITS: usize = 36;
+
+#[derive(Debug, PartialEq)]
+pub enum ProgramError {
+    BadMaskBit(char),
+    BadMaskLength(usize),
+    BadInstruction(String),
+}
+
+impl fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramError::BadMaskBit(c) => write!(f, "Bad mask bit {:?}, expected 0, 1, or X", c),
+            ProgramError::BadMaskLength(len) => {
+                write!(f, "Mask has {} bits, expected {}", len, MASK_BITS)
+            }
+            ProgramError::BadInstruction(line) => {
+                write!(
+                    f,
+                    "Expected mask = ... or mem[addr] = value, got {:?}",
+                    line
+                )
+            }
         }
-        let (addr, value) = scan_fmt!(&line, "mem[{}] = {}", u64, u64)?;
-        if is_part2() {
-            write_floating_memory(&mut memory, addr | or_mask, value, float_mask);
-        } else {
-            memory.insert(addr, value & and_mask | or_mask);
+    }
+}
+
+impl Error for ProgramError {}
+
+pub struct Puzzle {
+    program: Vec<Line>,
+}
+
+impl Puzzle {
+    fn run(&self, is_part2: bool) -> u64 {
+        let mut memory = HashMap::new();
+        let mut or_mask: u64 = 0;
+        let mut and_mask: u64 = u64::MAX;
+        let mut float_mask: u64 = 0;
+
+        for line in &self.program {
+            match *line {
+                Line::Mask(new_or_mask, new_and_mask, new_float_mask) => {
+                    or_mask = new_or_mask;
+                    and_mask = new_and_mask;
+                    float_mask = new_float_mask;
+                }
+                Line::Write(addr, value) if is_part2 => {
+                    write_floating_memory(&mut memory, addr | or_mask, value, float_mask);
+                }
+                Line::Write(addr, value) => {
+                    memory.insert(addr, value & and_mask | or_mask);
+                }
+            }
         }
+
+        memory.values().sum()
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = ProgramError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<ProgramError>> {
+        let program = parse_lines(input, parse_line)?;
+        Ok(Puzzle { program })
     }
 
-    println!("{}", memory.values().sum::<u64>());
+    fn part1(&self) -> Answer {
+        self.run(false).into()
+    }
 
-    Ok(())
+    fn part2(&self) -> Answer {
+        self.run(true).into()
+    }
+}
+
+fn parse_line(line: &str) -> Result<Line, ParseError<ProgramError>> {
+    const MASK: &str = "mask = ";
+    if let Some(mask) = line.strip_prefix(MASK) {
+        let (or_mask, and_mask, float_mask) =
+            parse_mask(mask).map_err(|err| err.offset_column(MASK.len()))?;
+        return Ok(Line::Mask(or_mask, and_mask, float_mask));
+    }
+    let (addr, value) = scan_fmt!(line, "mem[{}] = {}", u64, u64)
+        .map_err(|_| ParseError::at_column(1, ProgramError::BadInstruction(line.into())))?;
+    Ok(Line::Write(addr, value))
 }
 
-fn parse_mask(line: &str) -> Result<(u64, u64, u64), num::TryFromIntError> {
+fn parse_mask(mask: &str) -> Result<(u64, u64, u64), ParseError<ProgramError>> {
     let mut or_mask: u64 = 0;
     let mut and_mask: u64 = u64::MAX;
     let mut float_mask: u64 = 0;
 
-    for (ix, byte) in line.bytes().rev().enumerate() {
-        let bit = 2_u64.pow(ix.try_into()?);
-        match byte {
-            b'0' => and_mask -= bit,
-            b'1' => or_mask += bit,
-            b'X' => float_mask += bit,
-            _ => panic!("Bad byte {}", byte),
+    if mask.len() != MASK_BITS {
+        return Err(ParseError::at_column(
+            1,
+            ProgramError::BadMaskLength(mask.chars().count()),
+        ));
+    }
+    for (ix, c) in mask.chars().enumerate() {
+        let bit = 1 << (MASK_BITS - 1 - ix);
+        match c {
+            '0' => and_mask -= bit,
+            '1' => or_mask += bit,
+            'X' => float_mask += bit,
+            _ => return Err(ParseError::at_column(ix + 1, ProgramError::BadMaskBit(c))),
         }
     }
     Ok((or_mask, and_mask, float_mask))
@@ -72,10 +142,25 @@ fn write_floating_memory(memory: &mut HashMap<u64, u64>, addr: u64, value: u64,
     }
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
-
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
+#[test]
+fn test_parse_errors() {
+    let input = "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXXYX";
+    assert_eq!(
+        Puzzle::parse(input).err(),
+        Some(ParseError::new(3, 42, ProgramError::BadMaskBit('Y')))
+    );
+    assert_eq!(
+        Puzzle::parse("mask = 01X").err(),
+        Some(ParseError::new(1, 8, ProgramError::BadMaskLength(3)))
+    );
+    assert_eq!(
+        Puzzle::parse("mem[8] = 11\nmem[x] = 1").err(),
+        Some(ParseError::new(
+            2,
+            1,
+            ProgramError::BadInstruction("mem[x] = 1".into())
+        ))
+    );
 }
@@ -1,27 +1,69 @@
+use common::error::{column_of, ParseError};
+use common::solution::{Answer, Solution};
 use std::collections::HashMap;
-use std::env;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
 
-fn main() {
-    let input = vec![15, 12, 0, 14, 3, 1];
-    let mut last_seen: HashMap<usize, usize> = input
+#[derive(Debug, PartialEq)]
+pub enum GameError {
+    BadNumber(ParseIntError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::BadNumber(err) => write!(f, "Bad starting number: {}", err),
+        }
+    }
+}
+
+impl Error for GameError {}
+
+pub struct Puzzle {
+    starting_numbers: Vec<usize>,
+}
+
+impl Solution for Puzzle {
+    type Error = GameError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<GameError>> {
+        let starting_numbers = input
+            .trim_end()
+            .split(',')
+            .map(|s| {
+                s.parse().map_err(|err| {
+                    ParseError::at_column(column_of(input, s), GameError::BadNumber(err))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Puzzle { starting_numbers })
+    }
+
+    fn part1(&self) -> Answer {
+        play(&self.starting_numbers, 2020).into()
+    }
+
+    fn part2(&self) -> Answer {
+        play(&self.starting_numbers, 30000000).into()
+    }
+}
+
+fn play(starting_numbers: &[usize], n_turns: usize) -> usize {
+    let mut last_seen: HashMap<usize, usize> = starting_numbers
         .iter()
         .enumerate()
         .map(|(turn, starting_number)| (*starting_number, turn))
         .collect();
     let mut last_turn_number = 0;
 
-    for turn in (input.len() + 1)..if is_part2() { 30000000 } else { 2020 } {
+    for turn in (starting_numbers.len() + 1)..n_turns {
         let this_turn_number = match last_seen.get(&last_turn_number) {
             Some(prev_seen) => turn - 1 - prev_seen,
             None => 0,
         };
         last_seen.insert(last_turn_number, turn - 1);
         last_turn_number = this_turn_number;
-
-        println!("Turn {}: {}", turn + 1, this_turn_number);
     }
-}
-
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+    last_turn_number
 }
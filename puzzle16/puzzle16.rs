@@ -3,10 +3,41 @@ extern crate interval;
 #[macro_use]
 extern crate scan_fmt;
 
+use common::error::{column_of, lines_before, parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use gcollections::ops::set::{Contains, Union};
 use interval::interval_set::{IntervalSet, ToIntervalSet};
 use std::collections::HashSet;
-use std::env;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum TicketError {
+    MissingSection(&'static str),
+    ExpectedHeader(&'static str),
+    BadField(String),
+    BadRange(String),
+    BadNumber(ParseIntError),
+}
+
+impl fmt::Display for TicketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TicketError::MissingSection(section) => write!(f, "Missing {}", section),
+            TicketError::ExpectedHeader(header) => write!(f, "Expected {:?}", header),
+            TicketError::BadField(line) => {
+                write!(f, "Expected \"<field>: <ranges>\", got {:?}", line)
+            }
+            TicketError::BadRange(range) => {
+                write!(f, "Expected a range like 1-3, got {:?}", range)
+            }
+            TicketError::BadNumber(err) => write!(f, "Bad ticket value: {}", err),
+        }
+    }
+}
+
+impl Error for TicketError {}
 
 // https://stackoverflow.com/a/55292215/172999
 struct Multizip<T>(Vec<T>);
@@ -22,52 +53,82 @@ where
     }
 }
 
-fn main() {
-    let input = include_str!("input");
-    let mut blocks = input.split("\n\n");
-
-    let constraints_block = blocks.next().unwrap();
-    let field_descriptions: Vec<(&str, IntervalSet<u16>)> = constraints_block
-        .lines()
-        .map(|line| {
-            let mut parts = line.split(": ");
-            let field_name = parts.next().unwrap();
-            let interval_set = parts
-                .next()
-                .unwrap()
-                .split(" or ")
-                .map(|interval| scan_fmt!(interval, "{d}-{d}", u16, u16).unwrap())
-                .collect::<Vec<(u16, u16)>>()
-                .to_interval_set();
-            (field_name, interval_set)
-        })
-        .collect();
+pub struct Puzzle {
+    field_descriptions: Vec<(String, IntervalSet<u16>)>,
+    all_valid_values: IntervalSet<u16>,
+    my_ticket_values: Vec<u16>,
+    other_tickets: Vec<Vec<u16>>,
+}
+
+impl Solution for Puzzle {
+    type Error = TicketError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<TicketError>> {
+        let mut blocks = common::input::blocks(input);
+        let mut next_block = |section| {
+            blocks.next().ok_or_else(|| {
+                let end = input.lines().count() + 1;
+                ParseError::new(end, 1, TicketError::MissingSection(section))
+            })
+        };
+
+        let constraints_block = next_block("field descriptions")?;
+        let field_descriptions = parse_lines(constraints_block, parse_field)
+            .map_err(|err| err.offset_by(lines_before(input, constraints_block)))?;
+
+        let mut all_valid_values = vec![].to_interval_set();
+        for (_, interval) in &field_descriptions {
+            all_valid_values = all_valid_values.union(interval);
+        }
+
+        let my_ticket_block = next_block("your ticket")?;
+        let my_ticket_values = read_section(input, my_ticket_block, "your ticket:")?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let other_tickets_block = next_block("nearby tickets")?;
+        let other_tickets = read_section(input, other_tickets_block, "nearby tickets:")?;
 
-    let mut all_valid_values = vec![].to_interval_set();
-    for (_, interval) in &field_descriptions {
-        all_valid_values = all_valid_values.union(interval);
+        Ok(Puzzle {
+            field_descriptions,
+            all_valid_values,
+            my_ticket_values,
+            other_tickets,
+        })
     }
 
-    let my_ticket_block = blocks.next().unwrap();
-    assert!(my_ticket_block.starts_with("your ticket:\n"));
+    fn part1(&self) -> Answer {
+        let error_rate: u16 = self
+            .other_tickets
+            .iter()
+            .flat_map(|ticket| {
+                ticket
+                    .iter()
+                    .filter(|val| !self.all_valid_values.contains(val))
+            })
+            .sum();
+        error_rate.into()
+    }
 
-    let other_tickets_block = blocks.next().unwrap();
-    assert!(other_tickets_block.starts_with("nearby tickets:\n"));
-    let (valid_tickets, invalid_tickets): (Vec<Vec<u16>>, Vec<Vec<u16>>) = other_tickets_block
-        .lines()
-        .skip(1)
-        .map(read_csv_numbers)
-        .partition(|ticket| ticket.iter().all(|val| all_valid_values.contains(val)));
+    fn part2(&self) -> Answer {
+        let valid_tickets: Vec<&Vec<u16>> = self
+            .other_tickets
+            .iter()
+            .filter(|ticket| ticket.iter().all(|val| self.all_valid_values.contains(val)))
+            .collect();
 
-    if is_part2() {
-        let mut possible_fields_by_position: Vec<_> = (0..valid_tickets[0].len())
+        let Some(first_ticket) = valid_tickets.first() else {
+            return Answer::None; // nothing to determine fields from
+        };
+        let mut possible_fields_by_position: Vec<_> = (0..first_ticket.len())
             .map(|_| HashSet::new())
             .enumerate()
             .collect();
         for (position, position_values) in
             Multizip(valid_tickets.iter().map(|ticket| ticket.iter()).collect()).enumerate()
         {
-            for (field_ix, (_, interval)) in field_descriptions.iter().enumerate() {
+            for (field_ix, (_, interval)) in self.field_descriptions.iter().enumerate() {
                 if position_values.iter().all(|val| interval.contains(val)) {
                     possible_fields_by_position[position].1.insert(field_ix);
                 }
@@ -78,9 +139,10 @@ fn main() {
         possible_fields_by_position.reverse();
 
         let mut determined_fields_by_position = vec![0; possible_fields_by_position.len()];
-        while !possible_fields_by_position.is_empty() {
-            let (position, possible_fields) = possible_fields_by_position.pop().unwrap();
-            assert!(possible_fields.len() == 1, "unable to determine fields");
+        while let Some((position, possible_fields)) = possible_fields_by_position.pop() {
+            if possible_fields.len() != 1 {
+                return Answer::None; // unable to determine fields
+            }
             let field_ix = possible_fields.iter().next().unwrap();
             determined_fields_by_position[position] = *field_ix;
             for (_, remaining_fields) in &mut possible_fields_by_position {
@@ -88,34 +150,101 @@ fn main() {
             }
         }
 
-        let my_ticket_values: Vec<u16> = my_ticket_block
-            .lines()
-            .skip(1)
-            .flat_map(read_csv_numbers)
-            .collect();
-
         let answer: u64 = determined_fields_by_position
             .iter()
-            .map(|field_ix| field_descriptions[*field_ix].0)
-            .zip(my_ticket_values.iter())
+            .map(|field_ix| &self.field_descriptions[*field_ix].0)
+            .zip(self.my_ticket_values.iter())
             .filter(|(field_name, _)| field_name.starts_with("departure"))
             .map(|(_, value)| *value as u64)
             .product();
+        answer.into()
+    }
+}
 
-        println!("My ticket values {:?}", answer);
-    } else {
-        let error_rate: u16 = invalid_tickets
-            .iter()
-            .flat_map(|ticket| ticket.iter().filter(|val| !all_valid_values.contains(val)))
-            .sum();
-        println!("Error rate: {}", error_rate);
+fn parse_field(line: &str) -> Result<(String, IntervalSet<u16>), ParseError<TicketError>> {
+    let (field_name, ranges) = line
+        .split_once(": ")
+        .ok_or_else(|| ParseError::at_column(1, TicketError::BadField(line.into())))?;
+    let interval_set = ranges
+        .split(" or ")
+        .map(|range| {
+            scan_fmt!(range, "{d}-{d}", u16, u16).map_err(|_| {
+                ParseError::at_column(column_of(line, range), TicketError::BadRange(range.into()))
+            })
+        })
+        .collect::<Result<Vec<(u16, u16)>, _>>()?
+        .to_interval_set();
+    Ok((field_name.to_string(), interval_set))
+}
+
+// A block of tickets, one per line, below a header
+fn read_section(
+    input: &str,
+    block: &str,
+    header: &'static str,
+) -> Result<Vec<Vec<u16>>, ParseError<TicketError>> {
+    let first_line = lines_before(input, block);
+    let (first, rest) = block.split_once('\n').unwrap_or((block, ""));
+    if first != header {
+        return Err(ParseError::new(
+            first_line + 1,
+            1,
+            TicketError::ExpectedHeader(header),
+        ));
     }
+    parse_lines(rest, read_csv_numbers).map_err(|err| err.offset_by(first_line + 1))
+}
+
+fn read_csv_numbers(line: &str) -> Result<Vec<u16>, ParseError<TicketError>> {
+    line.split(',')
+        .map(|s| {
+            s.parse().map_err(|err| {
+                ParseError::at_column(column_of(line, s), TicketError::BadNumber(err))
+            })
+        })
+        .collect()
 }
 
-fn read_csv_numbers(line: &str) -> Vec<u16> {
-    line.split(',').map(|s| s.parse().unwrap()).collect()
+#[test]
+fn test_parse_errors() {
+    let input = "class: 1-3 or 5-7
+row: 6-11 or 33-44
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4x,50
+";
+    let err = Puzzle::parse(input).err().unwrap();
+    assert_eq!((err.line, err.column), (9, 4));
+    let input = input.replace("33-44", "33..44");
+    assert_eq!(
+        Puzzle::parse(&input).err(),
+        Some(ParseError::new(
+            2,
+            14,
+            TicketError::BadRange("33..44".into())
+        ))
+    );
+    let input = input
+        .replace("33..44", "33-44")
+        .replace("your ticket:", "my ticket:");
+    assert_eq!(
+        Puzzle::parse(&input).err(),
+        Some(ParseError::new(
+            4,
+            1,
+            TicketError::ExpectedHeader("your ticket:")
+        ))
+    );
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+#[test]
+fn test_no_valid_tickets() {
+    let input = "a: 1-2 or 4-5\n\nyour ticket:\n1\n\nnearby tickets:\n9\n";
+    let puzzle = Puzzle::parse(input).unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(9));
+    assert_eq!(puzzle.part2(), Answer::None);
 }
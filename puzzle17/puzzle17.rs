@@ -1,76 +1,139 @@
-use itertools::Itertools;
-use ndarray::{s, Array3};
-use std::iter;
+use automaton::render::{Draw, Frame, Layout, Renderer};
+use automaton::rule::Rule;
+use automaton::sparse::SparseAutomaton;
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
 
-fn main() {
-    // let input = ".#.\n..#\n###\n";
-    let input = include_str!("input");
-    let n_turns = 6;
-    let mut grid = read_grid(input, n_turns);
+#[derive(Debug, PartialEq)]
+pub enum CubeError {
+    Empty,
+    BadCube(char),
+    RaggedRow { expected: usize, got: usize },
+}
 
-    for _ in 0..n_turns {
-        let neighbours = calc_neighbours(&grid);
-        let activations = &neighbours.mapv(|count| (count == 3) as i16)
-            * &grid.mapv(|active| (active == 0) as i16);
-        let deactivations = &neighbours.mapv(|count| (count < 2 || count > 3) as i16) * &grid;
-        grid = grid + activations - deactivations;
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubeError::Empty => write!(f, "Initial slice is empty"),
+            CubeError::BadCube(c) => write!(f, "Bad cube {:?}, expected # or .", c),
+            CubeError::RaggedRow { expected, got } => {
+                write!(f, "Row is {} wide, but the first row is {}", got, expected)
+            }
+        }
     }
+}
 
-    dump_grid(&grid);
-    println!("{}", grid.sum());
+impl Error for CubeError {}
+
+pub struct Puzzle {
+    seed: Vec<Vec<u8>>,
 }
 
-fn calc_neighbours(grid: &Array3<i16>) -> Array3<i16> {
-    let shape = grid.shape();
-    let width = shape[0] as isize;
-    let height = shape[1] as isize;
-    let depth = shape[2] as isize;
-    let mut neighbours = Array3::<i16>::zeros(grid.raw_dim());
-    // Add slices of the occupied grid shifted one space in each direction
-    for starts in iter::repeat(-1..=1).take(3).multi_cartesian_product() {
-        if starts.iter().all(|start| *start == 0) {
-            continue;
-        }
-        let (xstart, ystart, zstart) = (starts[0], starts[1], starts[2]);
-        let xdest = xstart.max(0)..(width + xstart).min(width);
-        let ydest = ystart.max(0)..(height + ystart).min(height);
-        let zdest = zstart.max(0)..(depth + zstart).min(depth);
-        let xsource = (-xstart).max(0)..(width - xstart).min(width);
-        let ysource = (-ystart).max(0)..(height - ystart).min(height);
-        let zsource = (-zstart).max(0)..(depth - zstart).min(depth);
-        let mut slice = neighbours.slice_mut(s![xdest, ydest, zdest]);
-        slice += &grid.slice(s![xsource, ysource, zsource]);
+impl Solution for Puzzle {
+    type Error = CubeError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<CubeError>> {
+        let expected = match input.lines().next() {
+            Some(line) if !line.is_empty() => line.chars().count(),
+            _ => return Err(ParseError::new(1, 1, CubeError::Empty)),
+        };
+        let seed = parse_lines(input, |line| {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(ix, tile)| match tile {
+                    '#' => Ok(1),
+                    '.' => Ok(0),
+                    _ => Err(ParseError::at_column(ix + 1, CubeError::BadCube(tile))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if row.len() != expected {
+                let got = row.len();
+                return Err(ParseError::at_column(
+                    1,
+                    CubeError::RaggedRow { expected, got },
+                ));
+            }
+            Ok(row)
+        })?;
+        Ok(Puzzle { seed })
+    }
+
+    fn part1(&self) -> Answer {
+        self.count_active(3).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.count_active(4).into()
     }
-    neighbours
 }
 
-fn read_grid(input: &str, padding: usize) -> Array3<i16> {
-    let lines: Vec<&str> = input.lines().collect();
-    let height = lines.len();
-    let width = lines[0].len();
-    let mut cells = Array3::zeros((width + 2 * padding, height + 2 * padding, 2 * padding + 1));
-    for (y, line) in lines.iter().enumerate() {
-        for (x, tile) in line.bytes().enumerate() {
-            cells[[x + padding, y + padding, padding]] = match tile {
-                b'#' => 1,
-                b'.' => 0,
-                _ => panic!("Bad tile '{}'", tile),
-            };
+const N_TURNS: usize = 6;
+
+impl Puzzle {
+    // Runs the boot process in any number of dimensions. The seed is a 2D
+    // slice, so all the axes after the first two are symmetric. None for fewer
+    // than 2 dimensions, which can't hold the seed.
+    pub fn count_active(&self, dimensions: usize) -> Option<usize> {
+        if dimensions < 2 {
+            return None;
+        }
+        let mut automaton = self.boot(dimensions);
+        for _ in 0..N_TURNS {
+            automaton.step();
         }
+        Some(automaton.population())
+    }
+
+    fn boot(&self, dimensions: usize) -> SparseAutomaton {
+        let live = self.seed.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &active)| active != 0)
+                .map(move |(x, _)| {
+                    let mut cell = vec![y as i32, x as i32];
+                    cell.resize(dimensions, 0);
+                    cell
+                })
+        });
+        SparseAutomaton::new(dimensions, live, &Rule::life()).with_symmetric_axes(2)
     }
-    cells
 }
 
-fn dump_grid(grid: &Array3<i16>) {
-    for xy in grid.axis_iter(ndarray::Axis(2)) {
-        for x in xy.axis_iter(ndarray::Axis(1)) {
-            println!(
-                "{}",
-                x.mapv(|active| if active != 0 { '#' } else { '.' })
-                    .iter()
-                    .collect::<String>()
-            )
+// The slice where all coordinates but y and x are 0, after each turn
+impl Draw for Puzzle {
+    fn renderer(&self) -> Renderer {
+        Renderer::new(Layout::Square)
+    }
+
+    fn frames(&self, is_part2: bool) -> Vec<Frame> {
+        let dimensions = if is_part2 { 4 } else { 3 };
+        let rest = vec![0; dimensions - 2];
+        let mut automaton = self.boot(dimensions);
+        let mut frames = vec![Frame::of_sparse(&automaton, &rest)];
+        for _ in 0..N_TURNS {
+            automaton.step();
+            frames.push(Frame::of_sparse(&automaton, &rest));
         }
-        println!("");
+        frames
     }
 }
+
+#[test]
+fn test_example() {
+    let puzzle = Puzzle::parse(".#.\n..#\n###").unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(112));
+    assert_eq!(puzzle.part2(), Answer::from(848));
+    assert_eq!(puzzle.count_active(5), Some(5760));
+    assert_eq!(puzzle.count_active(1), None);
+    assert_eq!(puzzle.count_active(0), None);
+    assert!(puzzle.count_active(2).is_some());
+
+    // The z=0 slice after the first turn, as in the puzzle description
+    let frames = puzzle.frames(false);
+    assert_eq!(frames.len(), 7);
+    let art = puzzle.renderer().ascii(&frames[1]);
+    assert_eq!(art, "#.#\n.##\n.#.\n");
+}
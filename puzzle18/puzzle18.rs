@@ -1,6 +1,10 @@
 extern crate peg;
 
-use std::env;
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use peg::error::ExpectedSet;
+use std::error::Error;
+use std::fmt;
 
 peg::parser! {
     grammar bizarro_arithmetic() for str {
@@ -20,25 +24,61 @@ peg::parser! {
             n:number() { n }
             "(" e:expr2() ")" { e }
         }
+        // Accepts what both expr and expr2 do, without evaluating anything
+        pub rule valid() = term() ((" + " / " * ") term())*
+        rule term() = ['0'..='9'] / "(" valid() ")"
     }
 }
 
-fn main() {
-    let input = include_str!("input");
-    let answer: u64 = input
-        .lines()
-        .map(if is_part2() {
-            bizarro_arithmetic::expr2
-        } else {
-            bizarro_arithmetic::expr
-        })
-        .map(|n| n.unwrap())
-        .sum();
-    println!("{}", answer);
+#[derive(Debug, PartialEq)]
+pub enum HomeworkError {
+    Syntax(ExpectedSet),
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+impl fmt::Display for HomeworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HomeworkError::Syntax(expected) => write!(f, "Expected {}", expected),
+        }
+    }
+}
+
+impl Error for HomeworkError {}
+
+pub struct Puzzle {
+    lines: Vec<String>,
+}
+
+impl Solution for Puzzle {
+    type Error = HomeworkError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<HomeworkError>> {
+        let lines = parse_lines(input, |line| {
+            bizarro_arithmetic::valid(line).map_err(|err| {
+                ParseError::at_column(err.location.column, HomeworkError::Syntax(err.expected))
+            })?;
+            Ok(line.to_string())
+        })?;
+        Ok(Puzzle { lines })
+    }
+
+    fn part1(&self) -> Answer {
+        let answer: u64 = self
+            .lines
+            .iter()
+            .map(|line| bizarro_arithmetic::expr(line).unwrap()) // checked in parse
+            .sum();
+        answer.into()
+    }
+
+    fn part2(&self) -> Answer {
+        let answer: u64 = self
+            .lines
+            .iter()
+            .map(|line| bizarro_arithmetic::expr2(line).unwrap()) // checked in parse
+            .sum();
+        answer.into()
+    }
 }
 
 #[test]
@@ -84,3 +124,11 @@ fn part2_examples() {
         Ok(23340)
     );
 }
+
+#[test]
+fn parse_errors() {
+    let err = Puzzle::parse("1 + 2\n2 * (3 + 4").err().unwrap();
+    assert_eq!((err.line, err.column), (2, 11));
+    let err = Puzzle::parse("2 * 3 - 4").err().unwrap();
+    assert_eq!((err.line, err.column), (1, 6));
+}
@@ -1,10 +1,14 @@
 extern crate peg;
 
-use regex::Regex;
+use common::error::{column_of, lines_before, ParseError};
+use common::solution::{Answer, Solution};
+use peg::error::ExpectedSet;
 use std::collections::HashMap;
-use std::env;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Rule {
     Literal(char),
     Ref(usize),
@@ -14,10 +18,29 @@ pub enum Rule {
 
 type RuleSet = HashMap<usize, Rule>;
 
+#[derive(Debug, PartialEq)]
+pub enum RuleError {
+    MissingSection(&'static str),
+    Syntax(ExpectedSet),
+    UndefinedRule(usize),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::MissingSection(section) => write!(f, "Missing {}", section),
+            RuleError::Syntax(expected) => write!(f, "Expected {}", expected),
+            RuleError::UndefinedRule(ix) => write!(f, "Rule {} is not defined", ix),
+        }
+    }
+}
+
+impl Error for RuleError {}
+
 peg::parser! {
     grammar rules_grammar() for str {
-        rule index() -> usize = n:$(['0'..='9']+) ":" { n.parse().unwrap() }
-        rule number() -> Rule = n:$(['0'..='9']+) { Rule::Ref(n.parse().unwrap()) }
+        rule index() -> usize = n:$(['0'..='9']+) ":" {? n.parse().or(Err("rule number")) }
+        rule number() -> Rule = n:$(['0'..='9']+) {? n.parse().map(Rule::Ref).or(Err("rule number")) }
         rule literal() -> Rule = "\"" c:$(['a'..='z' | 'A'..='Z']) "\"" {
             Rule::Literal(c.chars().next().unwrap())
         }
@@ -31,70 +54,240 @@ peg::parser! {
     }
 }
 
-fn rule_to_regex(rule_set: &RuleSet, rule: &Rule, is_part2: bool) -> String {
-    use Rule::*;
+// Matches a message against a rule set by recursive descent, finding every
+// position where a rule starting at a given position could end. Rules can
+// refer to themselves, even before matching anything: the ends found for each
+// rule at each position are remembered, and a reference back to a rule that
+// is still being matched at the same position sees what the previous pass
+// found. Passes repeat until they find nothing new.
+struct Matcher {
+    // The rules numbered from 0 in the order of their numbers, so that rule
+    // numbers far apart don't leave gaps in the tables below. References to
+    // rules that don't exist point past the end. Shared, so that matching
+    // can hold on to a rule while updating the tables.
+    rules: Rc<[Rule]>,
+    // Where rule 0 went, if it exists
+    first: Option<usize>,
+    message: Vec<char>,
+    // Indexed by renumbered rule and then start position, with room for the
+    // longest message. The previous pass is only kept if there is a second.
+    width: usize,
+    previous: Vec<Option<Vec<usize>>>,
+    current: Vec<Option<Vec<usize>>>,
+    // Whether each rule is being matched at each position right now
+    active: Vec<bool>,
+    // Slots of current to clear before the next pass
+    filled: Vec<usize>,
+    used_guess: bool,
+}
+
+fn renumber(rule: &Rule, index: &HashMap<usize, usize>) -> Rule {
     match rule {
-        Literal(c) => c.to_string(),
-        Ref(ref_ix) => rule_index_to_regex(rule_set, *ref_ix, is_part2),
-        Seq(refs) => refs
+        Rule::Literal(c) => Rule::Literal(*c),
+        Rule::Ref(ix) => Rule::Ref(index.get(ix).copied().unwrap_or(usize::MAX)),
+        Rule::Seq(parts) => Rule::Seq(parts.iter().map(|part| renumber(part, index)).collect()),
+        Rule::Choice(l, r) => {
+            Rule::Choice(Box::new(renumber(l, index)), Box::new(renumber(r, index)))
+        }
+    }
+}
+
+impl Matcher {
+    fn new(rule_set: &RuleSet, longest: usize) -> Self {
+        let mut numbers: Vec<_> = rule_set.keys().copied().collect();
+        numbers.sort_unstable();
+        let index: HashMap<_, _> = numbers.iter().enumerate().map(|(i, &ix)| (ix, i)).collect();
+        let rules: Rc<[Rule]> = numbers
             .iter()
-            .map(|r| rule_to_regex(rule_set, r, is_part2))
-            .collect::<Vec<String>>()
-            .join(""),
-        Choice(l, r) => format!(
-            "({}|{})",
-            rule_to_regex(rule_set, &*l, is_part2),
-            rule_to_regex(rule_set, &*r, is_part2)
-        ),
-    }
-}
-
-fn rule_index_to_regex(rule_set: &RuleSet, ix: usize, is_part2: bool) -> String {
-    let rule = rule_set.get(&ix).unwrap();
-    match ix {
-        8 if is_part2 => format!("({})+", rule_to_regex(rule_set, rule, is_part2)),
-        11 if is_part2 => {
-            if let Rule::Seq(refs) = rule {
-                format!(
-                    "({0}{1}|{0}{{2}}{1}{{2}}|{0}{{3}}{1}{{3}}|{0}{{4}}{1}{{4}})",
-                    rule_to_regex(rule_set, &refs[0], is_part2),
-                    rule_to_regex(rule_set, &refs[1], is_part2)
-                )
-            } else {
-                panic!("Wrong type for rule 11");
+            .map(|ix| renumber(&rule_set[ix], &index))
+            .collect();
+        let width = longest + 1;
+        let size = rules.len() * width;
+        Matcher {
+            rules,
+            first: index.get(&0).copied(),
+            message: vec![],
+            width,
+            previous: vec![],
+            current: vec![None; size],
+            active: vec![false; size],
+            filled: vec![],
+            used_guess: false,
+        }
+    }
+
+    fn clear(&mut self) {
+        for slot in self.filled.drain(..) {
+            self.current[slot] = None;
+        }
+    }
+
+    fn accepts(&mut self, message: &str) -> bool {
+        self.message = message.chars().collect();
+        assert!(self.message.len() < self.width, "message too long");
+        self.previous.clear();
+        loop {
+            self.clear();
+            self.used_guess = false;
+            let ends = match self.first {
+                Some(first) => self.ref_ends(first, 0),
+                None => vec![],
+            };
+            if !self.used_guess || self.current == self.previous {
+                return ends.contains(&self.message.len());
             }
+            self.previous = self.current.clone();
+        }
+    }
+
+    fn ref_ends(&mut self, ix: usize, start: usize) -> Vec<usize> {
+        if ix >= self.rules.len() {
+            return vec![];
         }
-        _ => rule_to_regex(rule_set, rule, is_part2),
+        let rules = Rc::clone(&self.rules);
+        let slot = ix * self.width + start;
+        if let Some(ends) = &self.current[slot] {
+            self.used_guess |= self.active[slot];
+            return ends.clone();
+        }
+        let guess = self.previous.get(slot).cloned().flatten();
+        self.current[slot] = Some(guess.unwrap_or_default());
+        self.filled.push(slot);
+        self.active[slot] = true;
+        let ends = self.ends(&rules[ix], start);
+        self.active[slot] = false;
+        self.current[slot] = Some(ends.clone());
+        ends
+    }
+
+    // Sorted, without duplicates
+    fn ends(&mut self, rule: &Rule, start: usize) -> Vec<usize> {
+        use Rule::*;
+        let mut ends = match rule {
+            Literal(c) => {
+                return match self.message.get(start) {
+                    Some(next) if next == c => vec![start + 1],
+                    _ => vec![],
+                }
+            }
+            Ref(ix) => return self.ref_ends(*ix, start),
+            Seq(parts) => parts.iter().fold(vec![start], |starts, part| {
+                let mut ends: Vec<_> = starts
+                    .into_iter()
+                    .flat_map(|start| self.ends(part, start))
+                    .collect();
+                ends.sort_unstable();
+                ends.dedup();
+                ends
+            }),
+            Choice(l, r) => {
+                let mut ends = self.ends(l, start);
+                ends.extend(self.ends(r, start));
+                ends
+            }
+        };
+        ends.sort_unstable();
+        ends.dedup();
+        ends
     }
 }
 
-fn rule_set_to_regex(rule_set: &RuleSet, is_part2: bool) -> String {
-    format!("^{}$", rule_index_to_regex(rule_set, 0, is_part2))
+#[cfg(test)]
+fn matches(rule_set: &RuleSet, message: &str) -> bool {
+    Matcher::new(rule_set, message.chars().count()).accepts(message)
 }
 
-fn main() {
-    let input = include_str!("input");
-    let mut blocks = input.split("\n\n");
+// Part 2 replaces these rules with ones that loop
+static LOOPING_RULES: [&str; 2] = ["8: 42 | 42 8", "11: 42 31 | 42 11 31"];
 
-    let rules_block = blocks.next().unwrap();
-    let mut rule_set = RuleSet::new();
-    for line in rules_block.lines() {
+fn with_loops(rule_set: &RuleSet) -> RuleSet {
+    let mut looping = rule_set.clone();
+    for line in LOOPING_RULES {
         let (ix, rule) = rules_grammar::parse_line(line).unwrap();
-        rule_set.insert(ix, rule);
+        looping.insert(ix, rule);
     }
-    let matcher = Regex::new(&rule_set_to_regex(&rule_set, is_part2())).unwrap();
+    looping
+}
 
-    let messages_block = blocks.next().unwrap();
-    let matches = messages_block
-        .lines()
-        .filter(|line| matcher.is_match(line))
-        .count();
+pub struct Puzzle {
+    rule_set: RuleSet,
+    messages: Vec<String>,
+}
 
-    println!("{}", matches);
+impl Puzzle {
+    fn count_matches(&self, is_part2: bool) -> usize {
+        let looping;
+        let rule_set = if is_part2 {
+            looping = with_loops(&self.rule_set);
+            &looping
+        } else {
+            &self.rule_set
+        };
+        let longest = self.messages.iter().map(|line| line.chars().count()).max();
+        let mut matcher = Matcher::new(rule_set, longest.unwrap_or(0));
+        self.messages
+            .iter()
+            .filter(|line| matcher.accepts(line))
+            .count()
+    }
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+impl Solution for Puzzle {
+    type Error = RuleError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<RuleError>> {
+        let mut blocks = common::input::blocks(input);
+        let missing = |section| {
+            let end = input.lines().count() + 1;
+            ParseError::new(end, 1, RuleError::MissingSection(section))
+        };
+
+        let rules_block = blocks.next().ok_or_else(|| missing("rules"))?;
+        let first_line = lines_before(input, rules_block);
+        let mut rule_set = RuleSet::new();
+        for (line_ix, line) in rules_block.lines().enumerate() {
+            let (ix, rule) = rules_grammar::parse_line(line).map_err(|err| {
+                let kind = RuleError::Syntax(err.expected);
+                ParseError::new(first_line + line_ix + 1, err.location.column, kind)
+            })?;
+            rule_set.insert(ix, rule);
+        }
+        if !rule_set.contains_key(&0) {
+            return Err(ParseError::new(
+                first_line + 1,
+                1,
+                RuleError::UndefinedRule(0),
+            ));
+        }
+        for (line_ix, line) in rules_block.lines().enumerate() {
+            let (_, body) = line.split_once(": ").unwrap_or_default(); // checked above
+            for token in body.split(' ') {
+                match token.parse() {
+                    Ok(ix) if !rule_set.contains_key(&ix) => {
+                        return Err(ParseError::new(
+                            first_line + line_ix + 1,
+                            column_of(line, token),
+                            RuleError::UndefinedRule(ix),
+                        ));
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let messages_block = blocks.next().ok_or_else(|| missing("messages"))?;
+        let messages = messages_block.lines().map(String::from).collect();
+
+        Ok(Puzzle { rule_set, messages })
+    }
+
+    fn part1(&self) -> Answer {
+        self.count_matches(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.count_matches(true).into()
+    }
 }
 
 #[test]
@@ -118,7 +311,11 @@ fn example1() {
     );
     assert_eq!(rule_set.get(&3), Some(&Literal('b')));
 
-    assert_eq!(rule_set_to_regex(&rule_set, false), "^a(ab|ba)$");
+    assert!(matches(&rule_set, "aab"));
+    assert!(matches(&rule_set, "aba"));
+    assert!(!matches(&rule_set, "abb"));
+    assert!(!matches(&rule_set, "aa"));
+    assert!(!matches(&rule_set, "aabb"));
 }
 
 #[test]
@@ -163,15 +360,12 @@ fn example2() {
     assert_eq!(rule_set.get(&4), Some(&Literal('a')));
     assert_eq!(rule_set.get(&5), Some(&Literal('b')));
 
-    let regex = rule_set_to_regex(&rule_set, false);
-    assert_eq!(regex, "^a((aa|bb)(ab|ba)|(ab|ba)(aa|bb))b$");
-
-    let matcher = Regex::new(&regex).unwrap();
-    assert!(matcher.is_match("ababbb"));
-    assert!(!matcher.is_match("bababa"));
-    assert!(matcher.is_match("abbbab"));
-    assert!(!matcher.is_match("aaabbb"));
-    assert!(!matcher.is_match("aaaabbb"));
+    let is_match = |message| matches(&rule_set, message);
+    assert!(is_match("ababbb"));
+    assert!(!is_match("bababa"));
+    assert!(is_match("abbbab"));
+    assert!(!is_match("aaabbb"));
+    assert!(!is_match("aaaabbb"));
 }
 
 #[test]
@@ -214,39 +408,112 @@ fn example3() {
         let (ix, rule) = rules_grammar::parse_line(line).unwrap();
         rule_set.insert(ix, rule);
     }
-    let mut matcher = Regex::new(&rule_set_to_regex(&rule_set, false)).unwrap();
-
-    assert!(!matcher.is_match("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa"));
-    assert!(matcher.is_match("bbabbbbaabaabba"));
-    assert!(!matcher.is_match("babbbbaabbbbbabbbbbbaabaaabaaa"));
-    assert!(!matcher.is_match("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"));
-    assert!(!matcher.is_match("bbbbbbbaaaabbbbaaabbabaaa"));
-    assert!(!matcher.is_match("bbbababbbbaaaaaaaabbababaaababaabab"));
-    assert!(matcher.is_match("ababaaaaaabaaab"));
-    assert!(matcher.is_match("ababaaaaabbbaba"));
-    assert!(!matcher.is_match("baabbaaaabbaaaababbaababb"));
-    assert!(!matcher.is_match("abbbbabbbbaaaababbbbbbaaaababb"));
-    assert!(!matcher.is_match("aaaaabbaabaaaaababaa"));
-    assert!(!matcher.is_match("aaaabbaaaabbaaa"));
-    assert!(!matcher.is_match("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa"));
-    assert!(!matcher.is_match("babaaabbbaaabaababbaabababaaab"));
-    assert!(!matcher.is_match("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"));
-
-    matcher = Regex::new(&rule_set_to_regex(&rule_set, true)).unwrap();
-
-    assert!(!matcher.is_match("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa"));
-    assert!(matcher.is_match("bbabbbbaabaabba"));
-    assert!(matcher.is_match("babbbbaabbbbbabbbbbbaabaaabaaa"));
-    assert!(matcher.is_match("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"));
-    assert!(matcher.is_match("bbbbbbbaaaabbbbaaabbabaaa"));
-    assert!(matcher.is_match("bbbababbbbaaaaaaaabbababaaababaabab"));
-    assert!(matcher.is_match("ababaaaaaabaaab"));
-    assert!(matcher.is_match("ababaaaaabbbaba"));
-    assert!(matcher.is_match("baabbaaaabbaaaababbaababb"));
-    assert!(matcher.is_match("abbbbabbbbaaaababbbbbbaaaababb"));
-    assert!(matcher.is_match("aaaaabbaabaaaaababaa"));
-    assert!(!matcher.is_match("aaaabbaaaabbaaa"));
-    assert!(matcher.is_match("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa"));
-    assert!(!matcher.is_match("babaaabbbaaabaababbaabababaaab"));
-    assert!(matcher.is_match("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"));
+    let is_match = |message| matches(&rule_set, message);
+
+    assert!(!is_match("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa"));
+    assert!(is_match("bbabbbbaabaabba"));
+    assert!(!is_match("babbbbaabbbbbabbbbbbaabaaabaaa"));
+    assert!(!is_match("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"));
+    assert!(!is_match("bbbbbbbaaaabbbbaaabbabaaa"));
+    assert!(!is_match("bbbababbbbaaaaaaaabbababaaababaabab"));
+    assert!(is_match("ababaaaaaabaaab"));
+    assert!(is_match("ababaaaaabbbaba"));
+    assert!(!is_match("baabbaaaabbaaaababbaababb"));
+    assert!(!is_match("abbbbabbbbaaaababbbbbbaaaababb"));
+    assert!(!is_match("aaaaabbaabaaaaababaa"));
+    assert!(!is_match("aaaabbaaaabbaaa"));
+    assert!(!is_match("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa"));
+    assert!(!is_match("babaaabbbaaabaababbaabababaaab"));
+    assert!(!is_match("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"));
+
+    let looping = with_loops(&rule_set);
+    let is_match = |message| matches(&looping, message);
+
+    assert!(!is_match("abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa"));
+    assert!(is_match("bbabbbbaabaabba"));
+    assert!(is_match("babbbbaabbbbbabbbbbbaabaaabaaa"));
+    assert!(is_match("aaabbbbbbaaaabaababaabababbabaaabbababababaaa"));
+    assert!(is_match("bbbbbbbaaaabbbbaaabbabaaa"));
+    assert!(is_match("bbbababbbbaaaaaaaabbababaaababaabab"));
+    assert!(is_match("ababaaaaaabaaab"));
+    assert!(is_match("ababaaaaabbbaba"));
+    assert!(is_match("baabbaaaabbaaaababbaababb"));
+    assert!(is_match("abbbbabbbbaaaababbbbbbaaaababb"));
+    assert!(is_match("aaaaabbaabaaaaababaa"));
+    assert!(!is_match("aaaabbaaaabbaaa"));
+    assert!(is_match("aaaabbaabbaaaaaaabbbabbbaaabbaabaaa"));
+    assert!(!is_match("babaaabbbaaabaababbaabababaaab"));
+    assert!(is_match("aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"));
+}
+
+// Rule 11 nests deeper than any fixed number of repetitions
+#[test]
+fn deep_loops() {
+    let mut rule_set = RuleSet::new();
+    for line in ["0: 8 11", "8: 42", "11: 42 31", "42: \"a\"", "31: \"b\""] {
+        let (ix, rule) = rules_grammar::parse_line(line).unwrap();
+        rule_set.insert(ix, rule);
+    }
+    let looping = with_loops(&rule_set);
+    let message = |a, b| "a".repeat(a) + &"b".repeat(b);
+    assert!(matches(&rule_set, &message(2, 1)));
+    assert!(!matches(&rule_set, &message(3, 2)));
+    assert!(matches(&looping, &message(3, 2)));
+    assert!(matches(&looping, &message(7, 6)));
+    assert!(matches(&looping, &message(20, 6)));
+    assert!(!matches(&looping, &message(6, 6)));
+    assert!(!matches(&looping, &message(7, 7)));
+}
+
+// Rule numbers only take up room in the matcher for the rules that exist
+#[test]
+fn sparse_rule_numbers() {
+    let input = "0: 4000000000 7\n4000000000: \"a\"\n7: \"b\"\n\nab\nba\n";
+    let puzzle = Puzzle::parse(input).unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(1));
+    let matcher = Matcher::new(&puzzle.rule_set, 2);
+    assert_eq!(matcher.current.len(), 3 * 3);
+}
+
+// A rule can refer to itself before matching anything
+#[test]
+fn left_recursion() {
+    let mut rule_set = RuleSet::new();
+    for line in ["0: 3 | 0 1", "1: \"a\"", "2: \"b\"", "3: 2 | 3 2"] {
+        let (ix, rule) = rules_grammar::parse_line(line).unwrap();
+        rule_set.insert(ix, rule);
+    }
+    assert!(matches(&rule_set, "b"));
+    assert!(matches(&rule_set, "bbbaaaa"));
+    assert!(!matches(&rule_set, "bbaab"));
+    assert!(!matches(&rule_set, "a"));
+    assert!(!matches(&rule_set, ""));
+}
+
+#[test]
+fn parse_errors() {
+    let input = "0: 1 2\n1: \"a\"\n2: 1 3 | 13 1\n3: \"b\"\n\naab\n";
+    assert_eq!(
+        Puzzle::parse(input).err(),
+        Some(ParseError::new(3, 10, RuleError::UndefinedRule(13)))
+    );
+    let err = Puzzle::parse("0: 1 2\n1: a\n\naab\n").err().unwrap();
+    assert_eq!((err.line, err.column), (2, 4));
+    let huge = "99999999999999999999999";
+    let err = Puzzle::parse(&format!("0: 1\n1: {}\n\na\n", huge))
+        .err()
+        .unwrap();
+    assert_eq!((err.line, err.column), (2, 4 + huge.len()));
+    assert!(matches!(err.kind, RuleError::Syntax(_)));
+    let err = Puzzle::parse(&format!("{}: \"a\"\n\na\n", huge))
+        .err()
+        .unwrap();
+    assert_eq!(
+        (err.line, err.kind.to_string()),
+        (1, "Expected rule number".into())
+    );
+    assert_eq!(
+        Puzzle::parse("0: \"a\"\n").err(),
+        Some(ParseError::new(2, 1, RuleError::MissingSection("messages")))
+    );
 }
@@ -0,0 +1,98 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+
+#[macro_use]
+extern crate scan_fmt;
+
+#[derive(Debug)]
+struct PasswordRule {
+    min: usize,
+    max: usize,
+    letter: char,
+    password: String,
+}
+
+impl PasswordRule {
+    fn is_valid(&self, part2: bool) -> bool {
+        if part2 {
+            let first = self.password.chars().nth(self.min - 1);
+            let second = self.password.chars().nth(self.max - 1);
+            (first == Some(self.letter)) != (second == Some(self.letter))
+        } else {
+            let count = self.password.matches(self.letter).count();
+            count >= self.min && count <= self.max
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PolicyError {
+    BadLine(String),
+    BadRange(usize, usize),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::BadLine(line) => {
+                write!(
+                    f,
+                    "Expected \"<min>-<max> <letter>: <password>\", got {:?}",
+                    line
+                )
+            }
+            PolicyError::BadRange(min, max) => {
+                write!(f, "Bad range {}-{}, expected 1 <= min <= max", min, max)
+            }
+        }
+    }
+}
+
+impl Error for PolicyError {}
+
+pub struct Puzzle {
+    rules: Vec<PasswordRule>,
+}
+
+impl Puzzle {
+    fn count_valid(&self, is_part2: bool) -> usize {
+        self.rules
+            .iter()
+            .filter(|rule| rule.is_valid(is_part2))
+            .count()
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = PolicyError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<PolicyError>> {
+        let rules = parse_lines(input, parse_line)?;
+        Ok(Puzzle { rules })
+    }
+
+    fn part1(&self) -> Answer {
+        self.count_valid(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.count_valid(true).into()
+    }
+}
+
+fn parse_line(line: &str) -> Result<PasswordRule, ParseError<PolicyError>> {
+    let (min, max, letter, password) =
+        scan_fmt!(line, "{d}-{d} {}: {}", usize, usize, char, String)
+            .map_err(|_| ParseError::at_column(1, PolicyError::BadLine(line.into())))?;
+    if min == 0 || min > max {
+        return Err(ParseError::at_column(1, PolicyError::BadRange(min, max)));
+    }
+    Ok(PasswordRule {
+        min,
+        max,
+        letter,
+        password,
+    })
+}
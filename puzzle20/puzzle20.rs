@@ -1,535 +1,363 @@
 #[macro_use]
 extern crate scan_fmt;
 
-use bit_reverse::ParallelReverse;
-use itertools::Itertools;
-use multimap::MultiMap;
-use ndarray::{concatenate, s, Array2, ArrayView, ArrayView2, Axis, Ix1};
-use std::collections::HashSet;
-use std::convert::TryInto;
-use std::env;
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
-    TOP = 0,
-    RIGHT = 1,
-    BOTTOM = 2,
-    LEFT = 3,
-}
-
-impl Direction {
-    fn opposite(&self) -> Self {
-        use Direction::*;
-        match *self {
-            TOP => BOTTOM,
-            RIGHT => LEFT,
-            BOTTOM => TOP,
-            LEFT => RIGHT,
-        }
-    }
-
-    // returns the number of times to call rot90() on @other, to make it point
-    // the same way as @self
-    fn difference(self, other: Self) -> usize {
-        ((4 + (other as i8) - (self as i8)) % 4) as usize
-    }
-
-    fn all() -> [Direction; 4] {
-        use Direction::*;
-        [TOP, RIGHT, BOTTOM, LEFT]
-    }
+use automaton::render::{Draw, Frame, Layout, Palette, Renderer};
+use common::error::{lines_before, ParseError};
+use common::solution::{Answer, Solution};
+use jigsaw::assembly::{Assembly, JigsawError};
+use jigsaw::orientation::Orientation;
+use jigsaw::pattern::{Counting, Matches, Pattern};
+use jigsaw::search::assemble_unique;
+use jigsaw::tile::Tile;
+use ndarray::Array2;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+pub struct Puzzle {
+    assembly: Assembly,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-struct Tile {
-    id: u64,
-    // top, right, bottom, left borders with bits counted from LSB to MSB in
-    // clockwise direction
-    borders: [u16; 4],
-    border_bits: u8, // number of bits in border
-    image: Array2<u8>,
-}
+impl Solution for Puzzle {
+    type Error = TileError;
 
-impl Tile {
-    fn new(id: u64, grid: &Array2<u8>) -> Self {
-        let borders = [
-            s![0, ..],
-            s![.., grid.ncols() - 1],
-            s![grid.nrows() - 1, ..;-1],
-            s![..;-1, 0],
-        ]
-        .iter()
-        .map(|&slice| to_bits(grid.slice(slice)))
-        .collect::<Vec<u16>>()
-        .try_into()
-        .unwrap();
-        let image = grid
-            .slice(s![1..grid.nrows() - 1, 1..grid.ncols() - 1])
-            .into_owned();
-        Tile {
-            id,
-            borders,
-            image,
-            border_bits: grid.ncols() as u8,
-        }
+    fn parse(input: &str) -> Result<Self, ParseError<TileError>> {
+        Ok(Puzzle {
+            assembly: read_input(input)?,
+        })
     }
 
-    // rotate counterclockwise n times
-    fn rot90(&self, n: usize) -> Self {
-        let rotated_view = match n % 4 {
-            0 => self.image.view(),
-            1 => self.image.slice(s![.., ..;-1]).reversed_axes(),
-            2 => self.image.slice(s![..;-1, ..;-1]),
-            3 => self.image.slice(s![..;-1, ..]).reversed_axes(),
-            _ => panic!("Impossible"),
-        };
-        let rotated_image = rotated_view.into_owned();
-        let mut borders = self.borders;
-        borders.rotate_left(n);
-        Tile {
-            id: self.id,
-            borders,
-            image: rotated_image,
-            border_bits: self.border_bits,
-        }
+    fn part1(&self) -> Answer {
+        self.assembly.corners().iter().product::<u64>().into()
     }
 
-    fn fliplr(&self) -> Self {
-        let flipped_image = self.image.slice(s![.., ..;-1]).into_owned();
-        let mut borders: Vec<_> = self
-            .borders
-            .iter()
-            .map(|&b| flip_bits(b, self.border_bits))
-            .collect();
-        borders.reverse();
-        borders.rotate_right(1);
-        Tile {
-            id: self.id,
-            borders: borders.try_into().unwrap(),
-            image: flipped_image,
-            border_bits: self.border_bits,
+    fn part2(&self) -> Answer {
+        let found = self.find(&sea_monster(), Counting::Overlapping);
+        if found.matches.is_empty() {
+            return Answer::None;
         }
-    }
-
-    fn connection_side(&self, other: &Self) -> Option<Direction> {
-        let mut borders2: HashSet<_> = other.borders.iter().cloned().collect();
-        borders2.extend(
-            other
-                .borders
-                .iter()
-                .map(|&b| flip_bits(b, self.border_bits)),
-        );
-        for (&border, &direction) in self.borders.iter().zip(Direction::all().iter()) {
-            if borders2.contains(&border) {
-                return Some(direction);
-            }
-        }
-        None
-    }
-
-    fn connects_in_direction(&self, direction: Direction, other: &Self) -> Option<Direction> {
-        let border_to_connect = self.borders[direction as usize];
-        other
-            .borders
-            .iter()
-            .zip(Direction::all().iter())
-            .find(|(&border, _)| border == flip_bits(border_to_connect, self.border_bits))
-            .map(|(_, &other_side)| other_side)
-    }
-
-    fn match_other(&self, other: &Tile, direction: Direction) -> Option<Tile> {
-        match self.connects_in_direction(direction, other) {
-            None => (),
-            Some(dir) => {
-                let rotations = direction.opposite().difference(dir);
-                return Some(other.clone().rot90(rotations));
-            }
-        }
-        let flipped = other.fliplr();
-        match self.connects_in_direction(direction, &flipped) {
-            None => (),
-            Some(dir) => {
-                let rotations = direction.opposite().difference(dir);
-                return Some(flipped.rot90(rotations));
-            }
-        }
-        None
+        found.masked.iter().filter(|&&c| c > 0).count().into()
     }
 }
 
-fn flip_bits(border: u16, n_bits: u8) -> u16 {
-    assert!(n_bits <= 16);
-    border.swap_bits() >> (16 - n_bits)
-}
+static SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
 
-fn to_bits(slice: ArrayView<u8, Ix1>) -> u16 {
-    let mut retval = 0;
-    for (ix, &cell) in slice.iter().enumerate() {
-        if cell > 0 {
-            retval |= 2_u16.pow(ix.try_into().unwrap());
-        }
-    }
-    retval
+fn sea_monster() -> Pattern {
+    SEA_MONSTER.join("\n").parse().unwrap()
 }
 
-fn main() {
-    let input = include_str!("input");
-    let tiles = read_input(input);
-    let mut solver = Solver::new(&tiles);
-
-    if is_part2() {
-        let full_image = solver.arrange();
-        let (_, pixels) = all_orientations(&full_image)
-            .iter()
-            .find_map(|image| {
-                let (count, pixels) = count_sea_monsters(image);
-                if count != 0 {
-                    Some((count, pixels))
-                } else {
-                    None
-                }
-            })
-            .unwrap();
-        println!("{}", full_image.iter().filter(|&&c| c > 0).count() - pixels);
-    } else {
-        let answer: u64 = solver.corners.iter().product();
-        println!("{}", answer);
+impl Puzzle {
+    // Where a pattern appears in the assembled picture, turned any way
+    pub fn find(&self, pattern: &Pattern, counting: Counting) -> Matches {
+        pattern.find(self.assembly.image().view(), counting)
     }
 }
 
-struct Solver {
-    tiles: Vec<Tile>,
-    connections: MultiMap<u64, u64>,
-    corners: [u64; 4],
-    used_tile_ids: HashSet<u64>,
-}
+// Cell values in the frames: pixels are 0 or 1, and more than that for
+// borders, the gaps between tiles, and sea monsters
+const BORDER: u8 = 2;
+const GAP: u8 = 4;
+const MONSTER: u8 = 5;
+
+// The tiles as laid out, with their borders, in part 1; and the picture
+// turned so that the sea monsters are upright, in part 2
+impl Draw for Puzzle {
+    fn renderer(&self) -> Renderer {
+        let palette = vec![
+            ('.', [0x10, 0x30, 0x60]),
+            ('#', [0x60, 0xa0, 0xe0]),
+            ('.', [0x08, 0x18, 0x30]),
+            ('#', [0x30, 0x50, 0x70]),
+            (' ', [0x00, 0x00, 0x00]),
+            ('O', [0xf0, 0xc0, 0x20]),
+        ];
+        Renderer::new(Layout::Square)
+            .with_palette(Palette::new(palette))
+            .with_scale(4)
+    }
 
-impl Solver {
-    fn new(tiles: &[Tile]) -> Self {
-        let mut connections = MultiMap::new();
-        for (tile1, tile2) in tiles.iter().tuple_combinations() {
-            if tile1.connection_side(tile2).is_some() {
-                connections.insert(tile1.id, tile2.id);
-                connections.insert(tile2.id, tile1.id);
-            }
-        }
-        let corners: Vec<_> = tiles
-            .iter()
-            .map(|tile| tile.id)
-            .filter(|id| match connections.get_vec(id).unwrap().len() {
-                2 => true,
-                3 | 4 => false,
-                _ => panic!("Impossible"),
-            })
-            .collect();
-        Self {
-            tiles: tiles.to_vec(),
-            connections,
-            corners: corners.try_into().unwrap(),
-            used_tile_ids: HashSet::new(),
+    fn frames(&self, is_part2: bool) -> Vec<Frame> {
+        if !is_part2 {
+            return vec![Frame::new(self.tile_grid())];
         }
+        let found = self.find(&sea_monster(), Counting::Overlapping);
+        let highlighted = self.assembly.image() + found.mask * (MONSTER - 1);
+        let upright = found
+            .matches
+            .first()
+            .map_or(Orientation::IDENTITY, |first| first.orientation.inverse());
+        vec![Frame::new(upright.apply(highlighted.view()).into_owned())]
     }
 
-    fn find_and_orient_tile(&mut self, tile: &Tile, direction: Direction) -> Option<Tile> {
-        let tile_connections = self.connections.get_vec(&tile.id).unwrap();
-        let maybe_next_tile = self
-            .tiles
-            .iter()
-            .filter(|t| tile_connections.contains(&t.id) && !self.used_tile_ids.contains(&t.id))
-            .find_map(|candidate| tile.match_other(candidate, direction));
-        if let Some(t) = &maybe_next_tile {
-            self.used_tile_ids.insert(t.id);
+    fn legend(&self, is_part2: bool) -> Option<String> {
+        if is_part2 {
+            let found = self.find(&sea_monster(), Counting::Overlapping);
+            return Some(format!("{} sea monsters\n", found.matches.len()));
         }
-        maybe_next_tile
+        let mut legend = String::new();
+        for row in self.assembly.placements().outer_iter() {
+            let cells: Vec<_> = row
+                .iter()
+                .map(|placement| {
+                    let orientation = placement.orientation.to_string();
+                    format!("{:>6} {:<12}", placement.tile_id, orientation)
+                })
+                .collect();
+            legend.push_str(cells.join("").trim_end());
+            legend.push('\n');
+        }
+        Some(legend)
     }
+}
 
-    fn arrange(&mut self) -> Array2<u8> {
-        // Find top left corner - pick an arbitrary corner tile and rotate it until
-        // it has connections on the right and bottom
-        let mut tl_corner = self
-            .tiles
-            .iter()
-            .find(|tile| self.corners.contains(&tile.id))
+impl Puzzle {
+    // The tiles in place, borders and all, with a gap between each tile
+    fn tile_grid(&self) -> Array2<u8> {
+        let placements = self.assembly.placements();
+        let (rows, columns) = placements.dim();
+        let size = self
+            .assembly
+            .tile(placements[[0, 0]].tile_id)
             .unwrap()
-            .clone();
-        self.used_tile_ids.insert(tl_corner.id);
-        let tl_corner_connections: Vec<_> = self
-            .tiles
-            .iter()
-            .filter(|t| {
-                self.connections
-                    .get_vec(&tl_corner.id)
-                    .unwrap()
-                    .contains(&t.id)
-            })
-            .map(|candidate| tl_corner.connection_side(&candidate))
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .collect();
-        assert_eq!(tl_corner_connections.len(), 2);
-        tl_corner = tl_corner.rot90(match (tl_corner_connections[0], tl_corner_connections[1]) {
-            (Direction::RIGHT, Direction::BOTTOM) | (Direction::BOTTOM, Direction::RIGHT) => 0,
-            (Direction::LEFT, Direction::BOTTOM) | (Direction::BOTTOM, Direction::LEFT) => 1,
-            (Direction::LEFT, Direction::TOP) | (Direction::TOP, Direction::LEFT) => 2,
-            (Direction::RIGHT, Direction::TOP) | (Direction::TOP, Direction::RIGHT) => 3,
-            _ => panic!("Impossible {:?}", tl_corner_connections),
-        });
-
-        // Build the top edge
-        let mut t_row = vec![tl_corner];
-        loop {
-            match self.find_and_orient_tile(&&t_row[t_row.len() - 1], Direction::RIGHT) {
-                None => break,
-                Some(tile) => {
-                    t_row.push(tile);
-                }
+            .size();
+        let step = size + 1;
+        let mut cells = Array2::from_elem((rows * step - 1, columns * step - 1), GAP);
+        for ((row, column), placement) in placements.indexed_iter() {
+            let tile = self.assembly.tile(placement.tile_id).unwrap();
+            for ((y, x), &pixel) in tile.pixels(placement.orientation).indexed_iter() {
+                let on_border = y == 0 || x == 0 || y == size - 1 || x == size - 1;
+                let base = if on_border { BORDER } else { 0 };
+                cells[[row * step + y, column * step + x]] = base + pixel;
             }
         }
-
-        let ncols = t_row.len();
-        let nrows = self.tiles.len() / ncols;
-
-        println!("whole image is {}×{}", ncols, nrows);
-
-        // For each subsequent row...
-        let mut rows = vec![t_row];
-        for row in 1..nrows {
-            // Arrange the tiles that connect to the ones in the row above
-            rows.push(
-                (0..ncols)
-                    .map(|col| {
-                        self.find_and_orient_tile(&rows[row - 1][col], Direction::BOTTOM)
-                            .unwrap()
-                    })
-                    .collect(),
-            );
-        }
-
-        // Concatenate all the image data together
-        let all_rows: Vec<_> = rows
-            .iter()
-            .map(|row| {
-                let row_images: Vec<_> = row.iter().map(|t| t.image.view()).collect();
-                concatenate(Axis(1), &row_images).unwrap()
-            })
-            .collect();
-        concatenate(
-            Axis(0),
-            &all_rows.iter().map(|row| row.view()).collect::<Vec<_>>(),
-        )
-        .unwrap()
+        cells
     }
 }
 
-fn all_orientations(image: &Array2<u8>) -> [ArrayView2<u8>; 8] {
-    [
-        image.view(),
-        image.view().reversed_axes(),
-        image.slice(s![.., ..;-1]),
-        image.slice(s![.., ..;-1]).reversed_axes(),
-        image.slice(s![..;-1, ..]),
-        image.slice(s![..;-1, ..]).reversed_axes(),
-        image.slice(s![..;-1, ..;-1]),
-        image.slice(s![..;-1, ..;-1]).reversed_axes(),
-    ]
+#[derive(Debug, PartialEq)]
+pub enum TileError {
+    BadHeader(String),
+    BadPixel(char),
+    RaggedRow { expected: usize, got: usize },
+    Assembly(JigsawError),
 }
 
-static SEA_MONSTER: [&str; 3] = [
-    "                  # ",
-    "#    ##    ##    ###",
-    " #  #  #  #  #  #   ",
-];
-
-fn count_sea_monsters(image: &ArrayView2<u8>) -> (usize, usize) {
-    let mon_rows = SEA_MONSTER.len();
-    let mon_cols = SEA_MONSTER[0].len();
-    let mut sea_monster = Array2::zeros((mon_rows, mon_cols));
-    for (y, line) in SEA_MONSTER.iter().enumerate() {
-        for (x, cell) in line.bytes().enumerate() {
-            sea_monster[[y, x]] = (cell != b' ') as u8;
-        }
-    }
-    let mon_pixels: u8 = sea_monster.iter().sum();
-
-    let mut monsters = 0;
-    let rows = image.nrows();
-    let cols = image.ncols();
-    for y in 0..(rows - mon_rows) {
-        for x in 0..(cols - mon_cols) {
-            let slice = image.slice(s![y..(y + mon_rows), x..(x + mon_cols)]);
-            let correlation = &slice * &sea_monster.view();
-            if correlation.iter().sum::<u8>() == mon_pixels {
-                monsters += 1;
+impl fmt::Display for TileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileError::BadHeader(header) => {
+                write!(f, "Expected \"Tile <id>:\", got {:?}", header)
+            }
+            TileError::BadPixel(c) => write!(f, "Bad pixel {:?}, expected # or .", c),
+            TileError::RaggedRow { expected, got } => {
+                write!(f, "Row is {} wide, but the first row is {}", got, expected)
             }
+            TileError::Assembly(err) => write!(f, "{}", err),
         }
     }
-    (monsters, monsters * mon_pixels as usize)
 }
 
-fn read_grid(lines: &[&str]) -> Array2<u8> {
+impl Error for TileError {}
+
+// Line numbers in errors are relative to the first line of the grid. Whether
+// the grid is the right shape for a tile is up to Tile::new.
+fn read_grid(lines: &[&str]) -> Result<Array2<u8>, ParseError<TileError>> {
     let rows = lines.len();
-    let cols = lines[0].len();
+    let cols = lines.first().map_or(0, |line| line.len());
     let mut cells = Array2::zeros((rows, cols));
     for (y, line) in lines.iter().enumerate() {
-        for (x, tile) in line.bytes().enumerate() {
+        if line.len() != cols {
+            let kind = TileError::RaggedRow {
+                expected: cols,
+                got: line.len(),
+            };
+            return Err(ParseError::new(y + 1, 1, kind));
+        }
+        for (x, tile) in line.chars().enumerate() {
             cells[[y, x]] = match tile {
-                b'#' => 1,
-                b'.' => 0,
-                _ => panic!("Bad tile '{}'", tile),
+                '#' => 1,
+                '.' => 0,
+                _ => return Err(ParseError::new(y + 1, x + 1, TileError::BadPixel(tile))),
             };
         }
     }
-    cells
+    Ok(cells)
 }
 
-fn read_tile(input: &str) -> Tile {
+fn read_tile(input: &str) -> Result<Tile, ParseError<TileError>> {
     let mut lines = input.lines();
-    let header = lines.next().unwrap();
-    let id = scan_fmt!(header, "Tile {}:", u64).unwrap();
-    let grid = read_grid(&lines.collect::<Vec<&str>>());
-    Tile::new(id, &grid)
+    let header = lines.next().unwrap_or_default();
+    let id = scan_fmt!(header, "Tile {}:", u64)
+        .map_err(|_| ParseError::at_column(1, TileError::BadHeader(header.into())))?;
+    let grid = read_grid(&lines.collect::<Vec<&str>>()).map_err(|err| err.offset_by(1))?;
+    Tile::new(id, grid).map_err(|err| ParseError::at_column(1, TileError::Assembly(err)))
 }
 
-fn read_input(input: &'static str) -> Vec<Tile> {
-    input
-        .split("\n\n")
-        .filter(|s| !s.is_empty())
-        .map(read_tile)
-        .collect()
+// Reads the tiles and puts them together, searching for the only way they fit
+// if their borders match more than one other tile. Errors putting them
+// together point at the header of the tile they are about, if any.
+fn read_input(input: &str) -> Result<Assembly, ParseError<TileError>> {
+    let mut tiles = vec![];
+    let mut header_lines = HashMap::new();
+    for block in common::input::blocks(input) {
+        let first_line = lines_before(input, block);
+        let tile = read_tile(block).map_err(|err| err.offset_by(first_line))?;
+        header_lines.entry(tile.id()).or_insert(first_line + 1);
+        tiles.push(tile);
+    }
+    assemble_unique(&tiles).map_err(|err| {
+        let line = match err {
+            JigsawError::BadShape { id, .. }
+            | JigsawError::SizeMismatch { id, .. }
+            | JigsawError::DuplicateId(id)
+            | JigsawError::Ambiguous(id) => header_lines[&id],
+            _ => 1,
+        };
+        ParseError::new(line, 1, TileError::Assembly(err))
+    })
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+#[test]
+fn example() {
+    let puzzle = Puzzle::parse(include_str!("test_input")).unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(20899048083289_u64));
+
+    let sea_monster = sea_monster();
+    assert_eq!(sea_monster.pixel_count(), 15);
+    let found = puzzle.find(&sea_monster, Counting::Overlapping);
+    assert_eq!(found.matches.len(), 2);
+    assert_eq!(found.mask.iter().filter(|&&c| c > 0).count(), 30);
+    let separate = puzzle.find(&sea_monster, Counting::NonOverlapping);
+    assert_eq!(separate.matches, found.matches);
+    assert_eq!(found.masked.iter().filter(|&&c| c > 0).count(), 273);
+    assert_eq!(puzzle.part2(), Answer::from(273));
 }
 
 #[test]
-fn example() {
+fn parse_errors() {
     let input = include_str!("test_input");
-    let tiles = read_input(input);
-
+    let bad = input.replacen("..#.#.....", "..#.#..o..", 1);
+    let line = input.lines().position(|line| line == "..#.#.....").unwrap() + 1;
     assert_eq!(
-        tiles.iter().map(|t| t.id).collect::<Vec<u64>>(),
-        [2311, 1951, 1171, 1427, 1489, 2473, 2971, 2729, 3079]
+        Puzzle::parse(&bad).err(),
+        Some(ParseError::new(line, 8, TileError::BadPixel('o')))
     );
-
-    let mut solver = Solver::new(&tiles);
     assert_eq!(
-        tiles
-            .iter()
-            .map(|t| solver.connections.get_vec(&t.id).unwrap().len())
-            .collect::<Vec<usize>>(),
-        [3, 2, 2, 4, 3, 3, 2, 3, 2]
+        Puzzle::parse("Tile x:\n...\n...\n...\n").err(),
+        Some(ParseError::new(
+            1,
+            1,
+            TileError::BadHeader("Tile x:".into())
+        ))
     );
-
-    assert_eq!(solver.corners.len(), 4);
-
-    let full_image = solver.arrange();
-    let monsters = all_orientations(&full_image).iter().find_map(|image| {
-        let (count, pixels) = count_sea_monsters(image);
-        if count != 0 {
-            Some((count, pixels))
-        } else {
-            None
-        }
-    });
-    let (count, pixels) = monsters.unwrap();
-    assert_eq!(count, 2);
-    assert_eq!(pixels, 30);
-    assert_eq!(full_image.iter().filter(|&&c| c > 0).count() - pixels, 273);
-}
-
-#[test]
-fn direction_opposite() {
-    assert_eq!(Direction::LEFT.opposite(), Direction::RIGHT);
-    assert_eq!(Direction::RIGHT.opposite(), Direction::LEFT);
-    assert_eq!(Direction::TOP.opposite(), Direction::BOTTOM);
-    assert_eq!(Direction::BOTTOM.opposite(), Direction::TOP);
-}
-
-#[test]
-fn direction_difference() {
-    assert_eq!(Direction::LEFT.difference(Direction::LEFT), 0);
-    assert_eq!(Direction::RIGHT.difference(Direction::BOTTOM), 1);
-    assert_eq!(Direction::BOTTOM.difference(Direction::TOP), 2);
-    assert_eq!(Direction::RIGHT.difference(Direction::TOP), 3);
-}
-
-#[cfg(test)]
-#[rustfmt::skip]
-static TEST_TILE: [&'static str; 8] = [
-    ".#......",
-    "....###.",
-    "......##",
-    "........",
-    "#.#.....",
-    "..#.....",
-    "........",
-    ".....##.",
-];
-
-#[test]
-fn tile_read() {
-    let grid = read_grid(&TEST_TILE);
-    let tile = Tile::new(1, &grid);
-    assert_eq!(tile.id, 1);
-    assert_eq!(tile.borders, [2, 4, 6, 8]);
     assert_eq!(
-        tile.image,
-        ndarray::arr2(&[
-            [0, 0, 0, 1, 1, 1],
-            [0, 0, 0, 0, 0, 1],
-            [0, 0, 0, 0, 0, 0],
-            [0, 1, 0, 0, 0, 0],
-            [0, 1, 0, 0, 0, 0],
-            [0, 0, 0, 0, 0, 0],
-        ])
+        Puzzle::parse("Tile 1:\n...\n..\n...\n").err(),
+        Some(ParseError::new(
+            3,
+            1,
+            TileError::RaggedRow {
+                expected: 3,
+                got: 2
+            }
+        ))
     );
-}
-
-#[test]
-fn tile_rotate() {
-    let grid = read_grid(&TEST_TILE);
-    let tile = Tile::new(1, &grid).rot90(1);
-    assert_eq!(tile.id, 1);
-    assert_eq!(tile.borders, [4, 6, 8, 2]);
     assert_eq!(
-        tile.image,
-        ndarray::arr2(&[
-            [1, 1, 0, 0, 0, 0],
-            [1, 0, 0, 0, 0, 0],
-            [1, 0, 0, 0, 0, 0],
-            [0, 0, 0, 0, 0, 0],
-            [0, 0, 0, 1, 1, 0],
-            [0, 0, 0, 0, 0, 0],
-        ])
+        Puzzle::parse("Tile 1:\n...\n...\n").err(),
+        Some(ParseError::new(
+            1,
+            1,
+            TileError::Assembly(JigsawError::BadShape {
+                id: 1,
+                rows: 2,
+                columns: 3
+            })
+        ))
     );
-}
-
-#[test]
-fn tile_fliplr() {
-    let grid = read_grid(&TEST_TILE);
-    let tile = Tile::new(1, &grid).fliplr();
-    assert_eq!(tile.id, 1);
-    assert_eq!(tile.borders, [64, 16, 96, 32]);
+    let small = "Tile 7:\n...\n...\n...";
+    let mismatched = format!("{}\n\n{}", small, input);
+    let line = mismatched
+        .lines()
+        .position(|line| line == "Tile 2311:")
+        .unwrap()
+        + 1;
+    assert_eq!(
+        Puzzle::parse(&mismatched).err(),
+        Some(ParseError::new(
+            line,
+            1,
+            TileError::Assembly(JigsawError::SizeMismatch {
+                id: 2311,
+                expected: 3,
+                got: 10
+            })
+        ))
+    );
+    let input = include_str!("test_input");
+    let duplicated = input.replacen("Tile 1951:", "Tile 2311:", 1);
+    let line = input.lines().position(|line| line == "Tile 2311:").unwrap() + 1;
+    assert_eq!(
+        Puzzle::parse(&duplicated).err(),
+        Some(ParseError::new(
+            line,
+            1,
+            TileError::Assembly(JigsawError::DuplicateId(2311))
+        ))
+    );
+    // A copy of a tile under another number matches the same tiles as the
+    // original, so there is one tile too many for any grid
+    let copy = input
+        .split("\n\n")
+        .next()
+        .unwrap()
+        .replacen("2311", "9999", 1);
+    let extra = format!("{}\n\n{}", input.trim_end(), copy);
+    assert_eq!(
+        Puzzle::parse(&extra).err(),
+        Some(ParseError::new(
+            1,
+            1,
+            TileError::Assembly(JigsawError::NoAssembly)
+        ))
+    );
+    // Blank tiles fit together any way at all: 3 tiles in any order, each in
+    // any of 8 orientations, counting the 4 ways to turn and flip a row once
+    let blank: Vec<_> = (1..=3)
+        .map(|id| format!("Tile {}:\n...\n...\n...\n", id))
+        .collect();
     assert_eq!(
-        tile.image,
-        ndarray::arr2(&[
-            [1, 1, 1, 0, 0, 0],
-            [1, 0, 0, 0, 0, 0],
-            [0, 0, 0, 0, 0, 0],
-            [0, 0, 0, 0, 1, 0],
-            [0, 0, 0, 0, 1, 0],
-            [0, 0, 0, 0, 0, 0],
-        ])
+        Puzzle::parse(&blank.join("\n")).err(),
+        Some(ParseError::new(
+            1,
+            1,
+            TileError::Assembly(JigsawError::Multiple(6 * 512 / 4))
+        ))
     );
 }
 
 #[test]
-fn tile_rotate_isomorphic() {
-    let grid = read_grid(&TEST_TILE);
-    let tile = Tile::new(1, &grid);
-    assert_eq!(tile.rot90(0), tile);
-    assert_eq!(tile.rot90(2), tile.rot90(1).rot90(1));
-    assert_eq!(tile.rot90(3), tile.rot90(1).rot90(1).rot90(1));
-    assert_eq!(tile.rot90(4), tile);
+fn draw() {
+    let puzzle = Puzzle::parse(include_str!("test_input")).unwrap();
+    let renderer = puzzle.renderer();
+
+    let frames = puzzle.frames(false);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].cells.dim(), (32, 32));
+    assert_eq!(frames[0].cells[[10, 3]], GAP);
+    assert_eq!(frames[0].cells[[3, 10]], GAP);
+    let legend = puzzle.legend(false).unwrap();
+    assert_eq!(legend.lines().count(), 3);
+    assert!(legend.lines().nth(1).unwrap().contains("1427"));
+
+    let frames = puzzle.frames(true);
+    assert_eq!(frames.len(), 1);
+    let monster = frames[0].cells.iter().filter(|&&c| c == MONSTER).count();
+    assert_eq!(monster, 30);
+    let ascii = renderer.ascii(&frames[0]);
+    assert_eq!(ascii.lines().next(), Some(".####...#####..#...###.."));
+    assert_eq!(ascii.lines().nth(3), Some("#.O.##.OO#.#.OO.##.OOO##"));
+    assert_eq!(puzzle.legend(true).unwrap(), "2 sea monsters\n");
 }
@@ -1,5 +1,28 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use std::collections::{HashMap, HashSet};
-use std::env;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum FoodError {
+    MissingAllergens,
+}
+
+impl fmt::Display for FoodError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoodError::MissingAllergens => {
+                write!(
+                    f,
+                    "Expected the allergens at the end, in \"(contains ...)\""
+                )
+            }
+        }
+    }
+}
+
+impl Error for FoodError {}
 
 #[derive(Clone, Debug)]
 struct Food {
@@ -8,15 +31,17 @@ struct Food {
 }
 
 impl Food {
-    fn from_string(s: &str) -> Self {
-        let mut split1 = s[0..s.len() - 1].split(" (contains ");
-        let (ingredients_list, allergens_list) = (split1.next().unwrap(), split1.next().unwrap());
+    fn from_string(s: &str) -> Result<Self, ParseError<FoodError>> {
+        let (ingredients_list, allergens_list) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once(" (contains "))
+            .ok_or_else(|| ParseError::at_column(s.len() + 1, FoodError::MissingAllergens))?;
         let ingredients = ingredients_list.split(' ').map(String::from).collect();
         let allergens = allergens_list.split(", ").map(String::from).collect();
-        Food {
+        Ok(Food {
             ingredients,
             allergens,
-        }
+        })
     }
 }
 
@@ -57,69 +82,87 @@ fn find_non_allergens(possible_allergens: &HashMap<String, HashSet<String>>) ->
         .collect()
 }
 
+// None if at some point no ingredient is down to one possible allergen
 fn determine_allergens(
     possible_allergens: &HashMap<String, HashSet<String>>,
-) -> HashMap<String, String> {
+) -> Option<HashMap<String, String>> {
     let mut to_be_determined: Vec<_> = possible_allergens
         .iter()
         .map(|(s, set)| (s.clone(), set.clone()))
         .collect();
 
     let mut dangerous_ingredient_list = HashMap::new();
-    while !to_be_determined.is_empty() {
+    loop {
         to_be_determined.sort_by_key(|(_, set)| set.len());
         to_be_determined.reverse();
 
-        let (ingredient, allergens) = to_be_determined.pop().unwrap();
+        let Some((ingredient, allergens)) = to_be_determined.pop() else {
+            return Some(dangerous_ingredient_list);
+        };
         if allergens.is_empty() {
             continue;
         }
-        assert!(allergens.len() == 1, "unable to determine allergens");
-        let allergen = allergens.iter().next().unwrap();
-        dangerous_ingredient_list.insert(allergen.clone(), ingredient.clone());
+        if allergens.len() > 1 {
+            return None;
+        }
+        let allergen = allergens.into_iter().next()?;
         for (_, remaining_allergens) in &mut to_be_determined {
-            remaining_allergens.remove(allergen);
+            remaining_allergens.remove(&allergen);
         }
+        dangerous_ingredient_list.insert(allergen, ingredient);
     }
-    dangerous_ingredient_list
 }
 
-fn main() {
-    let input = include_str!("input");
-    let foods: Vec<Food> = input.lines().map(|s| Food::from_string(s)).collect();
-    let possible_allergens = find_possible_allergens(&foods);
-    let non_allergens = find_non_allergens(&possible_allergens);
-    if is_part2() {
-        let mut dangerous_ingredient_list = determine_allergens(&possible_allergens);
-        let mut dangerous_ingredients = dangerous_ingredient_list.drain().collect::<Vec<_>>();
-        dangerous_ingredients.sort_by(|(allergen1, _), (allergen2, _)| allergen1.cmp(allergen2));
-        let list = dangerous_ingredients
-            .drain(..)
-            .map(|(_, ingredient)| ingredient)
-            .collect::<Vec<_>>()
-            .join(",");
-        println!("{}", list);
-    } else {
+pub struct Puzzle {
+    foods: Vec<Food>,
+    possible_allergens: HashMap<String, HashSet<String>>,
+}
+
+impl Solution for Puzzle {
+    type Error = FoodError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<FoodError>> {
+        let foods = parse_lines(input, Food::from_string)?;
+        let possible_allergens = find_possible_allergens(&foods);
+        Ok(Puzzle {
+            foods,
+            possible_allergens,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let non_allergens = find_non_allergens(&self.possible_allergens);
         let count: usize = non_allergens
             .iter()
             .map(|ingredient| {
-                foods
+                self.foods
                     .iter()
                     .filter(|food| food.ingredients.contains(ingredient))
                     .count()
             })
             .sum();
-        println!("{}", count);
+        count.into()
     }
-}
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+    fn part2(&self) -> Answer {
+        let Some(mut dangerous_ingredient_list) = determine_allergens(&self.possible_allergens)
+        else {
+            return Answer::None;
+        };
+        let mut dangerous_ingredients = dangerous_ingredient_list.drain().collect::<Vec<_>>();
+        dangerous_ingredients.sort_by(|(allergen1, _), (allergen2, _)| allergen1.cmp(allergen2));
+        let list = dangerous_ingredients
+            .drain(..)
+            .map(|(_, ingredient)| ingredient)
+            .collect::<Vec<_>>()
+            .join(",");
+        list.into()
+    }
 }
 
 #[test]
 fn test_parse_food() {
-    let food = Food::from_string("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)");
+    let food = Food::from_string("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)").unwrap();
     assert_eq!(
         food.ingredients,
         ["mxmxvkd", "kfcds", "sqjhc", "nhms"]
@@ -146,7 +189,10 @@ fn test_example() {
         "sqjhc fvjkl (contains soy)",
         "sqjhc mxmxvkd sbzzf (contains fish)",
     ];
-    let foods: Vec<Food> = input.iter().map(|s| Food::from_string(s)).collect();
+    let foods: Vec<Food> = input
+        .iter()
+        .map(|s| Food::from_string(s).unwrap())
+        .collect();
     let possible_allergens = find_possible_allergens(&foods);
     let non_allergens = find_non_allergens(&possible_allergens);
     assert_eq!(
@@ -157,7 +203,7 @@ fn test_example() {
             .map(String::from)
             .collect()
     );
-    let dangerous_ingredient_list = determine_allergens(&possible_allergens);
+    let dangerous_ingredient_list = determine_allergens(&possible_allergens).unwrap();
     assert_eq!(
         dangerous_ingredient_list,
         [("dairy", "mxmxvkd"), ("fish", "sqjhc"), ("soy", "fvjkl")]
@@ -167,3 +213,11 @@ fn test_example() {
             .collect()
     );
 }
+
+// Either ingredient could have either allergen
+#[test]
+fn test_undetermined() {
+    let puzzle = Puzzle::parse("abc def (contains fish, soy)\n").unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(0));
+    assert_eq!(puzzle.part2(), Answer::None);
+}
@@ -1,26 +1,86 @@
+use common::error::{lines_before, parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use std::collections::{HashSet, VecDeque};
-use std::env;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
 
 type Deck = VecDeque<usize>;
 
-fn main() {
-    let input = include_str!("input");
-    let mut deck_blocks = input.split("\n\n");
-    let mut deck1 = read_deck(deck_blocks.next().unwrap());
-    let mut deck2 = read_deck(deck_blocks.next().unwrap());
-    if is_part2() {
-        play_recursive_combat(&mut deck1, &mut deck2);
-    } else {
-        play_combat(&mut deck1, &mut deck2);
+#[derive(Debug, PartialEq)]
+pub enum DeckError {
+    MissingDeck(u8),
+    ExpectedHeader(u8),
+    BadCard(ParseIntError),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::MissingDeck(player) => write!(f, "Missing player {}'s deck", player),
+            DeckError::ExpectedHeader(player) => write!(f, "Expected \"Player {}:\"", player),
+            DeckError::BadCard(err) => write!(f, "Bad card: {}", err),
+        }
     }
-    println!(
-        "{}",
+}
+
+impl Error for DeckError {}
+
+pub struct Puzzle {
+    deck1: Deck,
+    deck2: Deck,
+}
+
+impl Puzzle {
+    fn winning_score(&self, is_part2: bool) -> usize {
+        let mut deck1 = self.deck1.clone();
+        let mut deck2 = self.deck2.clone();
+        if is_part2 {
+            play_recursive_combat(&mut deck1, &mut deck2);
+        } else {
+            play_combat(&mut deck1, &mut deck2);
+        }
         score_deck(if deck1.is_empty() { &deck2 } else { &deck1 })
-    );
+    }
 }
 
-fn read_deck(block: &str) -> Deck {
-    block.lines().skip(1).map(|s| s.parse().unwrap()).collect()
+impl Solution for Puzzle {
+    type Error = DeckError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<DeckError>> {
+        let mut deck_blocks = common::input::blocks(input);
+        let mut next_deck = |player| {
+            let block = deck_blocks.next().ok_or_else(|| {
+                let end = input.lines().count() + 1;
+                ParseError::new(end, 1, DeckError::MissingDeck(player))
+            })?;
+            read_deck(block, player).map_err(|err| err.offset_by(lines_before(input, block)))
+        };
+        let deck1 = next_deck(1)?;
+        let deck2 = next_deck(2)?;
+        Ok(Puzzle { deck1, deck2 })
+    }
+
+    fn part1(&self) -> Answer {
+        self.winning_score(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.winning_score(true).into()
+    }
+}
+
+fn read_deck(block: &str, player: u8) -> Result<Deck, ParseError<DeckError>> {
+    let (header, cards) = block.split_once('\n').unwrap_or((block, ""));
+    if header != format!("Player {}:", player) {
+        return Err(ParseError::at_column(1, DeckError::ExpectedHeader(player)));
+    }
+    let deck = parse_lines(cards, |line| {
+        line.parse()
+            .map_err(|err| ParseError::at_column(1, DeckError::BadCard(err)))
+    })
+    .map_err(|err| err.offset_by(1))?;
+    Ok(deck.into())
 }
 
 fn play_combat(deck1: &mut Deck, deck2: &mut Deck) {
@@ -76,10 +136,6 @@ fn score_deck(deck: &Deck) -> usize {
         .sum()
 }
 
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
-}
-
 #[test]
 fn example_part1() {
     let mut deck1 = VecDeque::from(vec![9, 2, 6, 3, 1]);
@@ -1,5 +1,31 @@
+use common::error::ParseError;
+use common::solution::{Answer, Solution};
 use itertools::Itertools;
-use std::env;
+use std::error::Error;
+use std::fmt;
+
+const N_LABELS: usize = 9;
+
+#[derive(Debug, PartialEq)]
+pub enum CupError {
+    BadLabel(char),
+    DuplicateLabel(char),
+    MissingLabels(usize),
+}
+
+impl fmt::Display for CupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CupError::BadLabel(c) => write!(f, "Bad cup label {:?}, expected 1 to 9", c),
+            CupError::DuplicateLabel(c) => write!(f, "Cup {} appears twice", c),
+            CupError::MissingLabels(n) => {
+                write!(f, "Only {} cups, expected all of 1 to {}", n, N_LABELS)
+            }
+        }
+    }
+}
+
+impl Error for CupError {}
 
 fn dec_nonnegative_mod(num: usize, n_cups: usize) -> usize {
     (num + n_cups - 2) % n_cups + 1
@@ -57,16 +83,62 @@ impl Links {
     }
 }
 
-fn main() {
-    let input = "253149867";
-    let n_cups = if is_part2() { 1_000_000 } else { 9 };
-    let cups: Vec<usize> = input
-        .chars()
-        .map(|c| c.to_digit(10).unwrap() as usize)
-        .chain(10..(n_cups + 1))
+pub struct Puzzle {
+    labels: Vec<usize>,
+}
+
+impl Solution for Puzzle {
+    type Error = CupError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<CupError>> {
+        let mut labels: Vec<usize> = vec![];
+        for (ix, c) in input.trim_end().chars().enumerate() {
+            let at = |kind| ParseError::at_column(ix + 1, kind);
+            let label = match c.to_digit(10) {
+                Some(digit) if digit > 0 => digit as usize,
+                _ => return Err(at(CupError::BadLabel(c))),
+            };
+            if labels.contains(&label) {
+                return Err(at(CupError::DuplicateLabel(c)));
+            }
+            labels.push(label);
+        }
+        if labels.len() != N_LABELS {
+            let end = labels.len() + 1;
+            return Err(ParseError::at_column(
+                end,
+                CupError::MissingLabels(labels.len()),
+            ));
+        }
+        Ok(Puzzle { labels })
+    }
+
+    fn part1(&self) -> Answer {
+        let links = play(&self.labels, 9, 100);
+        let mut cup = links.next(1);
+        let mut order = vec![];
+        while cup != 1 {
+            order.push(cup.to_string());
+            cup = links.next(cup);
+        }
+        order.join("").into()
+    }
+
+    fn part2(&self) -> Answer {
+        let links = play(&self.labels, 1_000_000, 10_000_000);
+        let next = links.next(1);
+        let next2 = links.next(next);
+        (next * next2).into()
+    }
+}
+
+fn play(labels: &[usize], n_cups: usize, n_moves: u64) -> Links {
+    let cups: Vec<usize> = labels
+        .iter()
+        .copied()
+        .chain((labels.len() + 1)..(n_cups + 1))
         .collect();
     let mut links = Links::from_list(&cups);
-    let n_moves = if is_part2() { 10_000_000 } else { 100 };
     let progress = indicatif::ProgressBar::new(n_moves);
     progress.set_style(
         indicatif::ProgressStyle::default_bar()
@@ -77,23 +149,7 @@ fn main() {
         progress.inc(1);
     }
     progress.finish_and_clear();
-    if is_part2() {
-        let next = links.next(1);
-        let next2 = links.next(next);
-        println!("{}", next * next2);
-    } else {
-        let mut cup = links.next(1);
-        let mut order = vec![];
-        while cup != 1 {
-            order.push(cup.to_string());
-            cup = links.next(cup);
-        }
-        println!("{}", order.join(""));
-    }
-}
-
-fn is_part2() -> bool {
-    env::args().nth(1).map(|val| val == "2").unwrap_or(false)
+    links
 }
 
 #[test]
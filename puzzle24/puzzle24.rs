@@ -1,162 +1,197 @@
-#[macro_use]
-extern crate ndarray;
-
+use automaton::engine::Automaton;
+use automaton::hex::{Direction, Hex};
+use automaton::neighbourhood::Neighbourhood;
+use automaton::render::{Draw, Frame, Layout, Palette, Renderer};
+use automaton::rule::Rule;
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use multiset::HashMultiSet;
 use ndarray::Array2;
-
-type Hex = (i32, i32, i32);
+use std::error::Error;
+use std::fmt;
 
 #[derive(Debug, PartialEq)]
-enum Direction {
-    EAST,
-    SOUTHEAST,
-    SOUTHWEST,
-    WEST,
-    NORTHWEST,
-    NORTHEAST,
+pub enum DirectionError {
+    BadDirection(String),
 }
 
-impl Direction {
-    fn move_rel(&self, (x, y, z): Hex) -> Hex {
-        use Direction::*;
+impl fmt::Display for DirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            EAST => (x + 1, y - 1, z),
-            SOUTHEAST => (x, y - 1, z + 1),
-            SOUTHWEST => (x - 1, y, z + 1),
-            WEST => (x - 1, y + 1, z),
-            NORTHWEST => (x, y + 1, z - 1),
-            NORTHEAST => (x + 1, y, z - 1),
+            DirectionError::BadDirection(dir) => {
+                write!(
+                    f,
+                    "Bad direction {:?}, expected e, se, sw, w, nw, or ne",
+                    dir
+                )
+            }
         }
     }
 }
 
-fn parse_line(text: &str) -> Vec<Direction> {
-    use Direction::*;
-    let mut iter = text.bytes();
+impl Error for DirectionError {}
+
+fn parse_line(text: &str) -> Result<Vec<Direction>, ParseError<DirectionError>> {
+    let mut iter = text.char_indices();
     let mut retval = Vec::with_capacity(text.len() / 2);
-    while let Some(b) = iter.next() {
-        retval.push(match b {
-            b'e' => EAST,
-            b's' => match iter.next() {
-                Some(b2) if b2 == b'e' => SOUTHEAST,
-                Some(b2) if b2 == b'w' => SOUTHWEST,
-                Some(b2) => panic!("bad direction s{}", b2),
-                None => panic!("bad direction s"),
-            },
-            b'w' => WEST,
-            b'n' => match iter.next() {
-                Some(b2) if b2 == b'w' => NORTHWEST,
-                Some(b2) if b2 == b'e' => NORTHEAST,
-                Some(b2) => panic!("bad direction n{}", b2),
-                None => panic!("bad direction n"),
-            },
-            _ => panic!("bad direction {}", b),
-        });
+    while let Some((ix, c)) = iter.next() {
+        let mut name = c.to_string();
+        if let 's' | 'n' = c {
+            name.extend(iter.next().map(|(_, c2)| c2));
+        }
+        match Direction::from_name(&name) {
+            Some(direction) => retval.push(direction),
+            None => {
+                return Err(ParseError::at_column(
+                    ix + 1,
+                    DirectionError::BadDirection(name),
+                ))
+            }
+        }
     }
-    retval
+    Ok(retval)
 }
 
+// Black tiles are live cells, indexed [r, q] from the corner of a square
+// that holds all of the initial black tiles. A black tile with zero or more
+// than 2 black neighbours is flipped to white, and a white tile with exactly 2
+// black neighbours is flipped to black.
 struct Map {
-    map: Array2<i8>,
-    ref_q: i32,
-    ref_r: i32,
+    automaton: Automaton,
 }
 
 impl Map {
     fn from_counts(counts: &HashMultiSet<Hex>) -> Self {
-        let initial_extent = counts.distinct_elements().fold(0, |acc, (x, y, z)| {
-            acc.max(x.abs()).max(y.abs()).max(z.abs())
-        });
-        let extent = initial_extent + 100; // n_iterations = 100
+        let extent = counts
+            .distinct_elements()
+            .map(|hex| hex.length())
+            .max()
+            .unwrap_or(0);
         let size = extent as usize;
-        let map = Array2::zeros((2 * size + 1, 2 * size + 1));
-        let mut this = Self {
-            map,
-            ref_q: extent,
-            ref_r: extent,
-        };
-        for &(x, y, _) in counts
+        let mut map = Array2::zeros((2 * size + 1, 2 * size + 1));
+        for hex in counts
             .distinct_elements()
             .filter(|dest| counts.count_of(dest) % 2 == 1)
         {
-            this.set(x, y);
+            map[[(hex.r + extent) as usize, (hex.q + extent) as usize]] = 1;
+        }
+        let rule = Rule::new([2], [1, 2]);
+        Self {
+            automaton: Automaton::new(map.into_dyn(), Neighbourhood::Hex, &rule),
         }
-        this
     }
 
-    fn set(&mut self, x: i32, y: i32) {
-        let q = (x + self.ref_q) as usize;
-        let r = (y + self.ref_r) as usize;
-        self.map[[q, r]] = 1;
+    fn iterate(&mut self) {
+        self.automaton.step();
     }
 
-    fn calc_neighbours(map: &Array2<i8>) -> Array2<i8> {
-        let shape = map.shape();
-        let width = shape[0] as isize;
-        let height = shape[1] as isize;
-        let mut neighbours = Array2::zeros(map.raw_dim());
-        // Add slices of the occupied cells shifted one space in each hex
-        // direction
-        for &(xstart, ystart) in &[(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)] {
-            let xdest = xstart.max(0)..(width + xstart).min(width);
-            let ydest = ystart.max(0)..(height + ystart).min(height);
-            let xsource = (-xstart).max(0)..(width - xstart).min(width);
-            let ysource = (-ystart).max(0)..(height - ystart).min(height);
-            let mut slice = neighbours.slice_mut(s![xdest, ydest]);
-            slice += &map.slice(s![xsource, ysource]);
-        }
-        neighbours
+    fn count(&self) -> usize {
+        self.automaton.population()
     }
+}
 
-    fn iterate(&mut self) {
-        let neighbours = Map::calc_neighbours(&self.map);
-        let removals = &neighbours.mapv(|count| (count == 0 || count > 2) as i8) * &self.map;
-        let additions =
-            &neighbours.mapv(|count| (count == 2) as i8) * &self.map.mapv(|cell| (cell == 0) as i8);
-        self.map = &self.map + &additions - &removals;
+const N_DAYS: usize = 100;
+
+pub struct Puzzle {
+    destination_counts: HashMultiSet<Hex>,
+}
+
+impl Solution for Puzzle {
+    type Error = DirectionError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<DirectionError>> {
+        let paths = parse_lines(input, parse_line)?;
+        let destination_counts = paths
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .fold(Hex::ORIGIN, |hex, &dir| hex.neighbour(dir))
+            })
+            .collect();
+        Ok(Puzzle { destination_counts })
     }
 
-    fn count(&self) -> usize {
-        self.map
-            .fold(0, |acc, &cell| if cell > 0 { acc + 1 } else { acc })
+    fn part1(&self) -> Answer {
+        self.destination_counts
+            .distinct_elements()
+            .filter(|destination| self.destination_counts.count_of(destination) % 2 == 1)
+            .count()
+            .into()
+    }
+
+    fn part2(&self) -> Answer {
+        let mut map = Map::from_counts(&self.destination_counts);
+        for _ in 0..N_DAYS {
+            map.iterate();
+        }
+        map.count().into()
     }
 }
 
-fn main() {
-    let input = include_str!("input");
-    let destination_counts: HashMultiSet<_> = input
-        .lines()
-        .map(|line| {
-            parse_line(line)
-                .iter()
-                .fold((0, 0, 0), |hex, dir| dir.move_rel(hex))
-        })
-        .collect();
-    let count = destination_counts
-        .distinct_elements()
-        .filter(|destination| destination_counts.count_of(destination) % 2 == 1)
-        .count();
-    println!("{}", count);
+// The floor as laid out, then after each day for part 2
+impl Draw for Puzzle {
+    fn renderer(&self) -> Renderer {
+        let palette = vec![('o', [0xf0, 0xf0, 0xe8]), ('#', [0x18, 0x18, 0x18])];
+        Renderer::new(Layout::Hex).with_palette(Palette::new(palette))
+    }
 
-    let mut map = Map::from_counts(&destination_counts);
-    for _ in 0..100 {
-        map.iterate();
+    fn frames(&self, is_part2: bool) -> Vec<Frame> {
+        let mut map = Map::from_counts(&self.destination_counts);
+        let mut frames = vec![Frame::of(&map.automaton)];
+        if is_part2 {
+            for _ in 0..N_DAYS {
+                map.iterate();
+                frames.push(Frame::of(&map.automaton));
+            }
+        }
+        frames
     }
-    println!("{}", map.count());
 }
 
 #[test]
 fn test_parse() {
     use Direction::*;
     let input = "esenee";
-    assert_eq!(parse_line(&input), [EAST, SOUTHEAST, NORTHEAST, EAST]);
+    assert_eq!(
+        parse_line(input).unwrap(),
+        [East, SouthEast, NorthEast, East]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let bad = |column, dir: &str| {
+        Some(ParseError::new(
+            2,
+            column,
+            DirectionError::BadDirection(dir.into()),
+        ))
+    };
+    assert_eq!(Puzzle::parse("e\neesx").err(), bad(3, "sx"));
+    assert_eq!(Puzzle::parse("e\nwn").err(), bad(2, "n"));
+    assert_eq!(Puzzle::parse("e\nq").err(), bad(1, "q"));
+}
+
+// Rows are offset so that ne is up and to the right, and nw up and to the left
+#[test]
+fn test_draw() {
+    let puzzle = Puzzle::parse("e\nw\nne").unwrap();
+    let frames = puzzle.frames(false);
+    assert_eq!(frames.len(), 1);
+    assert_eq!(
+        puzzle.renderer().ascii(&frames[0]),
+        "o o #\n # o #\n  o o o\n"
+    );
+    assert_eq!(puzzle.frames(true).len(), 101);
 }
 
 #[test]
 fn test_move() {
-    let input = parse_line("esenee");
-    let end = input.iter().fold((0, 0, 0), |hex, dir| dir.move_rel(hex));
-    assert_eq!(end, (3, -3, 0));
+    let input = parse_line("esenee").unwrap();
+    let end = input
+        .iter()
+        .fold(Hex::ORIGIN, |hex, &dir| hex.neighbour(dir));
+    assert_eq!(end.cube(), (3, 0, -3));
 }
 
 #[test]
@@ -181,14 +216,7 @@ nenewswnwewswnenesenwnesewesw
 eneswnwswnwsenenwnwnwwseeswneewsenese
 neswnwewnwnwseenwseesewsenwsweewe
 wseweeenwnesenwwwswnew";
-    let destination_counts: HashMultiSet<_> = input
-        .lines()
-        .map(|line| {
-            parse_line(line)
-                .iter()
-                .fold((0, 0, 0), |hex, dir| dir.move_rel(hex))
-        })
-        .collect();
+    let destination_counts = Puzzle::parse(input).unwrap().destination_counts;
     let mut counts = vec![0, 0, 0];
     for destination in destination_counts.distinct_elements() {
         match destination_counts.count_of(destination) {
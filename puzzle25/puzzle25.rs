@@ -1,4 +1,33 @@
+use common::error::ParseError;
+use common::solution::{Answer, Solution};
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
+
+#[derive(Debug, PartialEq)]
+pub enum KeyError {
+    MissingKey(&'static str),
+    BadKey(ParseIntError),
+    OutOfRange(u64),
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::MissingKey(party) => write!(f, "Missing {} public key", party),
+            KeyError::BadKey(err) => write!(f, "Bad public key: {}", err),
+            KeyError::OutOfRange(key) => write!(
+                f,
+                "Public key {} is out of range, expected 1 to {}",
+                key,
+                MODULUS - 1
+            ),
+        }
+    }
+}
+
+impl Error for KeyError {}
 
 fn pow_m(base: u64, exponent: usize, modulus: u64) -> u64 {
     if modulus == 1 {
@@ -40,12 +69,15 @@ fn bsgs(base: u64, modulus: u64, result: u64) -> Option<usize> {
     None
 }
 
+const MODULUS: u64 = 20201227;
+
 fn transform_subject_number(subject_number: u64, loop_size: usize) -> u64 {
-    pow_m(subject_number, loop_size, 20201227)
+    pow_m(subject_number, loop_size, MODULUS)
 }
 
-fn guess_loop_size(public_key: u64) -> usize {
-    bsgs(7, 20201227, public_key).unwrap()
+// None if no loop size gives the key
+fn guess_loop_size(public_key: u64) -> Option<usize> {
+    bsgs(7, MODULUS, public_key)
 }
 
 #[derive(Debug)]
@@ -63,16 +95,55 @@ impl Party {
     }
 }
 
-fn main() {
-    let card_public_key = 2084668;
-    let door_public_key = 3704642;
-    let card = Party {
-        loop_size: guess_loop_size(card_public_key),
-    };
-    let door = Party {
-        loop_size: guess_loop_size(door_public_key),
-    };
-    println!("{}", card.encryption_key(door.public_key()));
+pub struct Puzzle {
+    card_public_key: u64,
+    door_public_key: u64,
+}
+
+impl Solution for Puzzle {
+    type Error = KeyError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<KeyError>> {
+        let mut lines = input.lines();
+        let mut next_key = |line, party| {
+            lines
+                .next()
+                .ok_or_else(|| ParseError::new(line, 1, KeyError::MissingKey(party)))?
+                .parse()
+                .map_err(|err| ParseError::new(line, 1, KeyError::BadKey(err)))
+                .and_then(|key| match key {
+                    1..MODULUS => Ok(key),
+                    _ => Err(ParseError::new(line, 1, KeyError::OutOfRange(key))),
+                })
+        };
+        let card_public_key = next_key(1, "card")?;
+        let door_public_key = next_key(2, "door")?;
+        Ok(Puzzle {
+            card_public_key,
+            door_public_key,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let (Some(card_loop_size), Some(door_loop_size)) = (
+            guess_loop_size(self.card_public_key),
+            guess_loop_size(self.door_public_key),
+        ) else {
+            return Answer::None;
+        };
+        let card = Party {
+            loop_size: card_loop_size,
+        };
+        let door = Party {
+            loop_size: door_loop_size,
+        };
+        card.encryption_key(door.public_key()).into()
+    }
+
+    // Day 25 only has one puzzle
+    fn part2(&self) -> Answer {
+        Answer::None
+    }
 }
 
 #[test]
@@ -85,8 +156,9 @@ fn test_transform_subject_number() {
 
 #[test]
 fn test_loop_size() {
-    assert_eq!(guess_loop_size(5764801), 8);
-    assert_eq!(guess_loop_size(17807724), 11);
+    assert_eq!(guess_loop_size(5764801), Some(8));
+    assert_eq!(guess_loop_size(17807724), Some(11));
+    assert_eq!(guess_loop_size(0), None);
 }
 
 #[test]
@@ -102,3 +174,19 @@ fn test_encryption_key() {
     assert_eq!(card.encryption_key(door.public_key()), 14897079);
     assert_eq!(door.encryption_key(card.public_key()), 14897079);
 }
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Puzzle::parse("5764801\n20201227\n").err(),
+        Some(ParseError::new(2, 1, KeyError::OutOfRange(20201227)))
+    );
+    assert_eq!(
+        Puzzle::parse("0\n17807724\n").err(),
+        Some(ParseError::new(1, 1, KeyError::OutOfRange(0)))
+    );
+    assert_eq!(
+        Puzzle::parse("5764801\n").err(),
+        Some(ParseError::new(2, 1, KeyError::MissingKey("door")))
+    );
+}
@@ -0,0 +1,71 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum LandscapeError {
+    EmptyRow,
+    BadSquare(char),
+}
+
+impl fmt::Display for LandscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LandscapeError::EmptyRow => write!(f, "Empty row"),
+            LandscapeError::BadSquare(c) => write!(f, "Bad square {:?}, expected . or #", c),
+        }
+    }
+}
+
+impl Error for LandscapeError {}
+
+pub struct Puzzle {
+    landscape: Vec<String>,
+}
+
+impl Solution for Puzzle {
+    type Error = LandscapeError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<LandscapeError>> {
+        let landscape = parse_lines(input, |line| {
+            if line.is_empty() {
+                return Err(ParseError::at_column(1, LandscapeError::EmptyRow));
+            }
+            match line.char_indices().find(|&(_, c)| c != '.' && c != '#') {
+                Some((ix, c)) => Err(ParseError::at_column(ix + 1, LandscapeError::BadSquare(c))),
+                None => Ok(line.to_string()),
+            }
+        })?;
+        Ok(Puzzle { landscape })
+    }
+
+    fn part1(&self) -> Answer {
+        count_trees_hit(&self.landscape, 3, 1).into()
+    }
+
+    fn part2(&self) -> Answer {
+        let total = count_trees_hit(&self.landscape, 1, 1)
+            * count_trees_hit(&self.landscape, 3, 1)
+            * count_trees_hit(&self.landscape, 5, 1)
+            * count_trees_hit(&self.landscape, 7, 1)
+            * count_trees_hit(&self.landscape, 1, 2);
+        total.into()
+    }
+}
+
+fn count_trees_hit(landscape: &[String], right: usize, down: usize) -> usize {
+    landscape
+        .iter()
+        .enumerate()
+        .filter(|(row_index, row): &(usize, &String)| hits_tree(*row_index, row, right, down))
+        .count()
+}
+
+fn hits_tree(row_index: usize, row: &String, right: usize, down: usize) -> bool {
+    if !row_index.is_multiple_of(down) {
+        return false;
+    }
+    let col_index = row_index / down * right % row.len();
+    row.as_bytes()[col_index] == b'#'
+}
@@ -0,0 +1,155 @@
+use common::error::{column_of, lines_before, ParseError};
+use common::input;
+use common::solution::{Answer, Solution};
+use regex::Regex;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[macro_use]
+extern crate lazy_static;
+
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+#[derive(Debug, PartialEq)]
+pub enum PassportError {
+    BadField(String),
+    UnknownField(String),
+}
+
+impl fmt::Display for PassportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassportError::BadField(field) => {
+                write!(f, "Expected <key>:<value>, got {:?}", field)
+            }
+            PassportError::UnknownField(key) => write!(
+                f,
+                "Unknown field {:?}, expected one of {}",
+                key,
+                FIELDS.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for PassportError {}
+
+pub struct Puzzle {
+    passports: Vec<HashMap<String, String>>,
+}
+
+impl Puzzle {
+    fn count_valid(&self, is_part2: bool) -> usize {
+        self.passports
+            .iter()
+            .filter(|passport| passport_is_valid(passport, is_part2))
+            .count()
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = PassportError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<PassportError>> {
+        let passports = input::blocks(input)
+            .map(|block| get_pairs(block).map_err(|err| err.offset_by(lines_before(input, block))))
+            .collect::<Result<_, _>>()?;
+        Ok(Puzzle { passports })
+    }
+
+    fn part1(&self) -> Answer {
+        self.count_valid(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.count_valid(true).into()
+    }
+}
+
+fn passport_is_valid(passport: &HashMap<String, String>, is_part2: bool) -> bool {
+    let n_keys = passport.len();
+    (n_keys == 8 || (n_keys == 7 && !passport.contains_key("cid")))
+        && (!is_part2
+            || valid_birth_year(&passport["byr"])
+                && valid_issue_year(&passport["iyr"])
+                && valid_expiry_year(&passport["eyr"])
+                && valid_height(&passport["hgt"])
+                && valid_hair_color(&passport["hcl"])
+                && valid_eye_color(&passport["ecl"])
+                && valid_passport_id(&passport["pid"]))
+}
+
+fn valid_birth_year(byr: &str) -> bool {
+    valid_year(byr, 1920, 2002)
+}
+
+fn valid_issue_year(iyr: &str) -> bool {
+    valid_year(iyr, 2010, 2020)
+}
+
+fn valid_expiry_year(eyr: &str) -> bool {
+    valid_year(eyr, 2020, 2030)
+}
+
+fn valid_year(y: &str, min: u16, max: u16) -> bool {
+    lazy_static! {
+        static ref YEAR_REGEX: Regex = Regex::new(r"^[12]\d{3}$").unwrap();
+    }
+    if !YEAR_REGEX.is_match(y) {
+        return false;
+    }
+    let year = y.parse::<u16>().unwrap();
+    year >= min && year <= max
+}
+
+fn valid_height(hgt: &str) -> bool {
+    lazy_static! {
+        static ref HEIGHT_REGEX: Regex = Regex::new(r"^(\d{2,3})(cm|in)$").unwrap();
+    }
+    let groups = match HEIGHT_REGEX.captures(hgt) {
+        Some(groups) => groups,
+        None => return false,
+    };
+    let height = groups[1].parse::<u8>().unwrap();
+    match &groups[2] {
+        "cm" => (150..=193).contains(&height),
+        "in" => (59..=76).contains(&height),
+        _ => false,
+    }
+}
+
+fn valid_hair_color(hcl: &str) -> bool {
+    lazy_static! {
+        static ref HAIR_REGEX: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
+    }
+    HAIR_REGEX.is_match(hcl)
+}
+
+fn valid_eye_color(ecl: &str) -> bool {
+    matches!(ecl, "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth")
+}
+
+fn valid_passport_id(pid: &str) -> bool {
+    lazy_static! {
+        static ref ID_REGEX: Regex = Regex::new(r"^\d{9}$").unwrap();
+    }
+    ID_REGEX.is_match(pid)
+}
+
+fn get_pairs(block: &str) -> Result<HashMap<String, String>, ParseError<PassportError>> {
+    let mut new_pairs = HashMap::new();
+    for (ix, line) in block.lines().enumerate() {
+        for pair in line.split_whitespace() {
+            let at = |kind| ParseError::new(ix + 1, column_of(line, pair), kind);
+            let (key, value) = pair
+                .split_once(':')
+                .ok_or_else(|| at(PassportError::BadField(pair.into())))?;
+            if !FIELDS.contains(&key) {
+                return Err(at(PassportError::UnknownField(key.into())));
+            }
+            new_pairs.insert(String::from(key), String::from(value));
+        }
+    }
+    Ok(new_pairs)
+}
@@ -0,0 +1,75 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use itertools::Itertools;
+use std::error::Error;
+use std::fmt;
+
+const CODE_LENGTH: usize = 10;
+
+#[derive(Debug, PartialEq)]
+pub enum SeatError {
+    BadLength(usize),
+    BadLetter(char),
+}
+
+impl fmt::Display for SeatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeatError::BadLength(len) => {
+                write!(f, "Seat code is {} letters, expected {}", len, CODE_LENGTH)
+            }
+            SeatError::BadLetter(c) => write!(f, "Bad letter {:?}, expected F, B, L, or R", c),
+        }
+    }
+}
+
+impl Error for SeatError {}
+
+pub struct Puzzle {
+    seat_ids: Vec<u16>,
+}
+
+impl Solution for Puzzle {
+    type Error = SeatError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<SeatError>> {
+        let mut seat_ids = parse_lines(input, |line| {
+            if let Some((ix, c)) = line.char_indices().find(|&(_, c)| !"FBLR".contains(c)) {
+                return Err(ParseError::at_column(ix + 1, SeatError::BadLetter(c)));
+            }
+            if line.len() != CODE_LENGTH {
+                return Err(ParseError::at_column(1, SeatError::BadLength(line.len())));
+            }
+            Ok(code_to_seat_id(line))
+        })?;
+        seat_ids.sort_unstable();
+        Ok(Puzzle { seat_ids })
+    }
+
+    fn part1(&self) -> Answer {
+        self.seat_ids.last().copied().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.seat_ids
+            .iter()
+            .tuple_windows()
+            .find(|(seat, next)| **next != **seat + 1)
+            .map(|(seat_before, _)| seat_before + 1)
+            .into()
+    }
+}
+
+fn code_to_seat_id(line: &str) -> u16 {
+    let maxbyte = line.len() - 1;
+    line.bytes()
+        .enumerate()
+        .map(|(index, byte): (usize, u8)| {
+            let digit = match byte {
+                b'B' | b'R' => 1,
+                _ => 0,
+            };
+            digit << (maxbyte - index)
+        })
+        .sum()
+}
@@ -0,0 +1,92 @@
+use common::error::{lines_before, parse_lines, ParseError};
+use common::input;
+use common::solution::{Answer, Solution};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum AnswerError {
+    BadQuestion(char),
+}
+
+impl fmt::Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerError::BadQuestion(c) => write!(f, "Bad question {:?}, expected a to z", c),
+        }
+    }
+}
+
+impl Error for AnswerError {}
+
+pub struct Puzzle {
+    groups: Vec<String>,
+}
+
+impl Puzzle {
+    fn total(&self, is_part2: bool) -> usize {
+        self.groups
+            .iter()
+            .map(|group| count_answers(group, is_part2))
+            .sum()
+    }
+}
+
+impl Solution for Puzzle {
+    type Error = AnswerError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<AnswerError>> {
+        let groups = input::blocks(input)
+            .map(|block| {
+                parse_lines(block, |line| {
+                    match line.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+                        Some((ix, c)) => {
+                            Err(ParseError::at_column(ix + 1, AnswerError::BadQuestion(c)))
+                        }
+                        None => Ok(()),
+                    }
+                })
+                .map(|_| block.to_string())
+                .map_err(|err| err.offset_by(lines_before(input, block)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Puzzle { groups })
+    }
+
+    fn part1(&self) -> Answer {
+        self.total(false).into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.total(true).into()
+    }
+}
+
+fn count_answers(group_block: &str, is_part2: bool) -> usize {
+    let mut group = HashMap::new();
+    let mut group_size = 0;
+    for line in group_block.lines() {
+        for byte in line.bytes() {
+            let count = group.entry(byte).or_insert(0);
+            *count += 1;
+        }
+        group_size += 1;
+    }
+    if is_part2 {
+        group
+            .iter()
+            .filter(|(_, count): &(&u8, &usize)| **count == group_size)
+            .count()
+    } else {
+        group.len()
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        Puzzle::parse("abc\n\na\nb\nC\n").err(),
+        Some(ParseError::new(5, 1, AnswerError::BadQuestion('C')))
+    );
+}
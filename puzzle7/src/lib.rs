@@ -0,0 +1,160 @@
+use common::error::{column_of, parse_lines, ParseError};
+use common::solution::{Answer, Solution};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+#[macro_use]
+extern crate scan_fmt;
+
+pub struct Puzzle {
+    container_rules: HashMap<String, Vec<String>>,
+    contents_rules: HashMap<String, Vec<(usize, String)>>,
+}
+
+impl Solution for Puzzle {
+    type Error = RuleError;
+
+    fn parse(input: &str) -> Result<Self, ParseError<RuleError>> {
+        parse_rules(input)
+    }
+
+    // No answer if there is no shiny gold bag in the rules
+    fn part1(&self) -> Answer {
+        if !self.container_rules.contains_key("shiny gold") {
+            return Answer::None;
+        }
+        all_containers_for(&self.container_rules, "shiny gold")
+            .len()
+            .into()
+    }
+
+    // No answer if there is no rule for what a shiny gold bag, or any bag
+    // inside it, contains
+    fn part2(&self) -> Answer {
+        total_contained_by(&self.contents_rules, "shiny gold").into()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RuleError {
+    BadRule(String),
+    BadContents(String),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::BadRule(line) => write!(
+                f,
+                "Expected \"<color> bags contain <contents>.\", got {:?}",
+                line
+            ),
+            RuleError::BadContents(bag) => {
+                write!(f, "Expected \"<n> <color> bag(s)\", got {:?}", bag)
+            }
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+fn parse_rules(input: &str) -> Result<Puzzle, ParseError<RuleError>> {
+    let mut container_rules = HashMap::new();
+    let mut contents_rules = HashMap::new();
+    let rules = parse_lines(input, parse_rule)?;
+    for (container, contents) in rules {
+        container_rules.entry(container.clone()).or_insert(vec![]);
+        let contents_entry = contents_rules.entry(container.clone()).or_insert(vec![]);
+        for (num, color) in contents {
+            let container_entry = container_rules.entry(color.clone()).or_insert(vec![]);
+            container_entry.push(container.clone());
+            contents_entry.push((num, color));
+        }
+    }
+
+    Ok(Puzzle {
+        container_rules,
+        contents_rules,
+    })
+}
+
+type Rule = (String, Vec<(usize, String)>);
+
+fn parse_rule(line: &str) -> Result<Rule, ParseError<RuleError>> {
+    let (container, contents) = line
+        .strip_suffix('.')
+        .and_then(|rule| rule.split_once(" bags contain "))
+        .filter(|(container, _)| container.split(' ').count() == 2)
+        .ok_or_else(|| ParseError::at_column(1, RuleError::BadRule(line.into())))?;
+    if contents == "no other bags" {
+        return Ok((container.into(), vec![]));
+    }
+    let contents = contents
+        .split(", ")
+        .map(|bag| {
+            let (num, adjective, color) = scan_fmt!(bag, "{d} {} {} bag", usize, String, String)
+                .map_err(|_| {
+                    ParseError::at_column(column_of(line, bag), RuleError::BadContents(bag.into()))
+                })?;
+            Ok((num, format!("{} {}", adjective, color)))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((container.into(), contents))
+}
+
+fn total_contained_by(
+    rules: &HashMap<String, Vec<(usize, String)>>,
+    bag_color: &str,
+) -> Option<usize> {
+    rules
+        .get(bag_color)?
+        .iter()
+        .map(|(num, color)| Some(num * (total_contained_by(rules, color)? + 1)))
+        .sum()
+}
+
+fn all_containers_for(rules: &HashMap<String, Vec<String>>, bag_color: &str) -> HashSet<String> {
+    let containers = rules.get(bag_color).map_or(&[][..], |c| c.as_slice());
+    let mut colors: HashSet<String> = containers.iter().cloned().collect();
+    for color in containers.iter() {
+        let mut indirect_colors = all_containers_for(rules, color);
+        colors.extend(indirect_colors.drain());
+    }
+    colors
+}
+
+#[test]
+fn test_parse_errors() {
+    let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, nine faded blue bags.
+faded blue bags contain no other bags";
+    assert_eq!(
+        Puzzle::parse(input).err(),
+        Some(ParseError::new(
+            3,
+            46,
+            RuleError::BadContents("nine faded blue bags".into())
+        ))
+    );
+    let input = "faded blue bags contain no other bags";
+    assert_eq!(
+        Puzzle::parse(input).err(),
+        Some(ParseError::new(1, 1, RuleError::BadRule(input.into())))
+    );
+}
+
+#[test]
+fn test_no_shiny_gold() {
+    let puzzle = Puzzle::parse("").unwrap();
+    assert_eq!(puzzle.part1(), Answer::None);
+    assert_eq!(puzzle.part2(), Answer::None);
+    let puzzle = Puzzle::parse("faded blue bags contain no other bags.").unwrap();
+    assert_eq!(puzzle.part1(), Answer::None);
+    assert_eq!(puzzle.part2(), Answer::None);
+    // Nothing says what a dull red bag contains
+    let puzzle = Puzzle::parse("shiny gold bags contain 2 dull red bags.").unwrap();
+    assert_eq!(puzzle.part1(), Answer::from(0));
+    assert_eq!(puzzle.part2(), Answer::None);
+}
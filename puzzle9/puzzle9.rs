@@ -1,17 +1,49 @@
+use common::error::{parse_lines, ParseError};
+use common::solution::{Answer, Solution};
 use itertools::Itertools;
-use std::fs;
-use std::io::{self, BufRead};
+use std::error::Error;
+use std::fmt;
+use std::num::ParseIntError;
 
-fn main() -> Result<(), io::Error> {
-    let file = fs::File::open("input")?;
-    let numbers: Vec<u64> = read_lines(file).map(|s| s.parse().unwrap()).collect();
+#[derive(Debug, PartialEq)]
+pub enum XmasError {
+    BadNumber(ParseIntError),
+}
+
+impl fmt::Display for XmasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmasError::BadNumber(err) => write!(f, "Bad number: {}", err),
+        }
+    }
+}
+
+impl Error for XmasError {}
+
+pub struct Puzzle {
+    numbers: Vec<u64>,
+}
+
+impl Solution for Puzzle {
+    type Error = XmasError;
 
-    let answer = invalid_number(&numbers, 25).unwrap();
+    fn parse(input: &str) -> Result<Self, ParseError<XmasError>> {
+        let numbers = parse_lines(input, |line| {
+            line.parse()
+                .map_err(|err| ParseError::at_column(1, XmasError::BadNumber(err)))
+        })?;
+        Ok(Puzzle { numbers })
+    }
 
-    println!("Part 1: {}", answer);
-    println!("Part 2: {}", encryption_weakness(&numbers, answer).unwrap());
+    fn part1(&self) -> Answer {
+        invalid_number(&self.numbers, 25).into()
+    }
 
-    Ok(())
+    fn part2(&self) -> Answer {
+        invalid_number(&self.numbers, 25)
+            .and_then(|answer| encryption_weakness(&self.numbers, answer))
+            .into()
+    }
 }
 
 fn invalid_number(numbers: &[u64], window_size: usize) -> Option<u64> {
@@ -40,7 +72,3 @@ fn encryption_weakness(numbers: &[u64], invalid_number: u64) -> Option<u64> {
     }
     None
 }
-
-fn read_lines(file: fs::File) -> impl Iterator<Item = String> {
-    io::BufReader::new(file).lines().map(|res| res.unwrap())
-}